@@ -1,15 +1,42 @@
 #[macro_use]
 extern crate clap;
-use crate::service::{cache, converter::convert_website_object, live::live, render};
+use crate::service::{
+    alias, cache, chart, compare, converter::convert_website_object, country, group, live::live,
+    overrides, profile, render, season, stats, svg, theme, validate, view,
+};
 use clap::App;
+use model::{
+    config::{Config, FixtureMode, ViewConfig},
+    render::{OutputFormat, RenderOptions},
+};
 use service::handler;
-use std::{path::Path, sync::Arc};
+use std::{fs, path::Path, sync::Arc};
+use utils::date::DateFormatter;
 
 mod model;
 mod service;
 mod utils;
 mod web;
 
+// overrides and the active profile only last for this run, so undo both
+// before writing
+fn save_config(
+    config: &Config,
+    layers: &overrides::Layers,
+    profile_name: &str,
+    path: &str,
+) -> bool {
+    let mut config = match layers.restore(config) {
+        Ok(config) => config,
+        Err(err) => {
+            println!("[ERROR] Config not written, err={}", err);
+            return false;
+        }
+    };
+    profile::swap(&mut config, profile_name);
+    return config.write_to_file(path);
+}
+
 fn main() {
     let yaml = load_yaml!("./cli.yaml");
     let matches = App::from_yaml(yaml).get_matches();
@@ -25,33 +52,151 @@ fn main() {
         println!("[INFO] Currently in verbose mode");
     }
 
-    let config_path = matches.value_of("config").unwrap_or("./conf.json");
-    let config = service::config::read_config(config_path);
-
-    if matches.is_present("show_config") {
-        println!("🔧 Config loaded from json :");
-        println!("{}", config.to_json());
+    let config_location = service::config::locate_config(matches.value_of("config"));
+    let config_path = config_location.path.as_str();
+    if is_verbose {
+        println!(
+            "[INFO] Config path={}, from {}",
+            config_path, config_location.source
+        );
+    }
+    if let Some(config_matches) = matches.subcommand_matches("config") {
+        if config_matches.subcommand_matches("check").is_some()
+            && !service::config::check_config(config_path)
+        {
+            std::process::exit(1);
+        }
         return;
     }
 
+    let mut config = service::config::read_config(config_path);
+
     if matches.is_present("show_config_path") {
+        // a new or unreadable path is still worth showing as it is
         let path = Path::new(config_path);
-        println!("{}", path.canonicalize().unwrap().display());
+        match path.canonicalize() {
+            Ok(path) => println!("{}", path.display()),
+            Err(_) => println!("{}", config_path),
+        }
+        println!("[INFO] Picked from {}", config_location.source);
         return;
     }
 
-    let settings = model::config::Settings {
+    if let Some(profile_matches) = matches.subcommand_matches("profile") {
+        if profile::subcommand_match(profile_matches, &mut config)
+            && config.write_to_file(config_path)
+        {
+            println!("[INFO] 🌟 Config written to path={}", config_path);
+        } else {
+            println!("[INFO] 😱 Config file unchanged, path={}", config_path);
+        }
+        return;
+    }
+
+    let profile_name = matches
+        .value_of("profile")
+        .unwrap_or(&config.current_profile)
+        .to_string();
+    if !profile::swap(&mut config, &profile_name) {
+        println!(
+            "[WARN] Profile doesn't exist, use default profile, profile={}",
+            profile_name
+        );
+    } else if is_verbose && !profile_name.is_empty() {
+        println!("[INFO] Using profile={}", profile_name);
+    }
+
+    let file_config = config.clone();
+    let sets = matches.values_of("set").into_iter().flatten().collect();
+    let overrides = match overrides::collect(sets) {
+        Ok(overrides) => overrides,
+        Err(err) => {
+            println!("[ERROR] Invalid override, err={}", err);
+            std::process::exit(1);
+        }
+    };
+    match overrides::apply(&config, &overrides) {
+        Ok(overridden) => config = overridden,
+        Err(err) => {
+            println!("[ERROR] Invalid override, err={}", err);
+            std::process::exit(1);
+        }
+    }
+    if is_verbose {
+        for item in overrides.iter() {
+            println!(
+                "[INFO] Override {}={}, from {}",
+                item.key, item.value, item.source
+            );
+        }
+    }
+    let layers = overrides::Layers {
+        file: file_config,
+        merged: config.clone(),
+        overrides,
+    };
+    // checked after the overrides, so --set can fix a bad value for a run;
+    // editing subcommands still run so the config can be fixed from here
+    let config_errors: Vec<validate::Issue> = validate::validate(&config)
+        .into_iter()
+        .filter(|issue| issue.severity == validate::Severity::Error)
+        .collect();
+
+    if matches.is_present("show_config") {
+        println!(
+            "🔧 Config loaded from {} :",
+            model::config::ConfigFormat::from_path(config_path)
+        );
+        println!("{}", config.to_json());
+        return;
+    }
+
+    let mut date_config = config.date.clone();
+    if let Some(locale) = matches.value_of("locale") {
+        date_config.locale = locale.to_string();
+    }
+    if let Some(timezone) = matches.value_of("timezone") {
+        date_config.timezone = timezone.to_string();
+    }
+    if let Some(format) = matches.value_of("date_format") {
+        date_config.format = format.to_string();
+    }
+
+    let output_theme = theme::select(matches.value_of("theme").unwrap_or(&config.theme));
+
+    let mut settings = model::config::Settings {
         config,
         verbose: is_verbose,
+        date: DateFormatter::new(&date_config),
+        theme: output_theme,
     };
+
+    settings.config.leetcode.request.config_dir = Path::new(config_path)
+        .parent()
+        .map(|dir| dir.to_string_lossy().to_string())
+        .unwrap_or_default();
+    if let Some(dir) = matches.value_of("record") {
+        settings.config.leetcode.request.fixture_mode = FixtureMode::Record;
+        settings.config.leetcode.request.fixture_dir = dir.to_string();
+    } else if let Some(dir) = matches.value_of("replay") {
+        settings.config.leetcode.request.fixture_mode = FixtureMode::Replay;
+        settings.config.leetcode.request.fixture_dir = dir.to_string();
+    }
+    if is_verbose && settings.config.leetcode.request.fixture_mode != FixtureMode::Off {
+        println!(
+            "[INFO] Fixture mode enabled, dir={}",
+            settings.config.leetcode.request.fixture_dir
+        );
+    }
+
     let handlers = handler::handler_hashmap();
 
     for (website_name, handler) in handlers.iter() {
         if let Some(website_matches) = matches.subcommand_matches(website_name) {
             let mut settings = settings;
 
-            if (handler.subcommand_match)(website_matches, &mut settings)
-                && settings.config.write_to_file(config_path)
+            if (handler.subcommand_match)(website_matches, &mut settings, runtime.clone())
+                && save_config(&settings.config, &layers, &profile_name, config_path)
             {
                 println!("[INFO] 🌟 Config written to path={}", config_path);
             } else {
@@ -62,29 +207,312 @@ fn main() {
         }
     }
 
+    if let Some(group_matches) = matches.subcommand_matches("group") {
+        if group::subcommand_match(group_matches, &mut settings.config)
+            && save_config(&settings.config, &layers, &profile_name, config_path)
+        {
+            println!("[INFO] 🌟 Config written to path={}", config_path);
+        } else {
+            println!("[INFO] 😱 Config file unchanged, path={}", config_path);
+        }
+        return;
+    }
+
+    if !config_errors.is_empty() {
+        for issue in config_errors.iter() {
+            issue.print();
+        }
+        println!(
+            "[ERROR] Config has {} error(s), run config check for details, path={}",
+            config_errors.len(),
+            config_path
+        );
+        std::process::exit(1);
+    }
+
     let is_live = matches.is_present("live");
     if is_verbose && is_live {
         println!("[INFO] Currently in live mode");
     }
+    let is_history = matches.is_present("history");
+    let history_last = match matches.value_of("history_last") {
+        Some(last) => match last.parse::<usize>() {
+            Ok(last) => last,
+            Err(_) => {
+                println!("[ERROR] Invalid --history-last {}, expected a number", last);
+                std::process::exit(1);
+            }
+        },
+        None => 0,
+    };
+    if is_verbose && is_history {
+        println!("[INFO] Currently in history mode");
+    }
     let hide_submission = matches.is_present("hide_submission");
     if is_verbose && hide_submission {
         println!("[INFO] Submission info is hidden in output");
     }
 
+    let view_name = matches.value_of("view").unwrap_or("default");
+    let mut view_config = match settings.config.views.get(view_name) {
+        Some(view_config) => view_config.clone(),
+        None => {
+            if matches.is_present("view") {
+                println!(
+                    "[WARN] View doesn't exist, use default view, view={}",
+                    view_name
+                );
+            }
+            ViewConfig::default()
+        }
+    };
+    if let Some(columns) = matches.values_of("columns") {
+        view_config.columns = columns.map(|column| column.to_string()).collect();
+    }
+    if let Some(sort) = matches.value_of("sort") {
+        view_config.sort = sort.to_string();
+    }
+    if let Some(users) = matches.values_of("only_users") {
+        view_config.users = users.map(|user| user.to_string()).collect();
+    }
+    if let Some(countries) = matches.values_of("only_countries") {
+        view_config.countries = countries.map(|country| country.to_string()).collect();
+    }
+    if let Some(top) = matches.value_of("top") {
+        view_config.top = match top.parse::<usize>() {
+            Ok(top) => top,
+            Err(_) => {
+                println!("[ERROR] Invalid --top {}, expected a number", top);
+                std::process::exit(1);
+            }
+        };
+    }
+
+    if let Some(name) = matches.value_of("save_view") {
+        settings
+            .config
+            .views
+            .insert(name.to_string(), view_config.clone());
+        if save_config(&settings.config, &layers, &profile_name, config_path) {
+            println!("[INFO] 🌟 View {} saved to path={}", name, config_path);
+        } else {
+            println!("[INFO] 😱 Config file unchanged, path={}", config_path);
+        }
+        return;
+    }
+
+    let options = RenderOptions {
+        format: match matches.value_of("format") {
+            Some("markdown") => OutputFormat::Markdown,
+            Some("json") => OutputFormat::Json,
+            _ => OutputFormat::Text,
+        },
+        hide_submission,
+        full_title: matches.is_present("full_title"),
+        show_attempts: matches.is_present("attempts"),
+        date: settings.date.clone(),
+        theme: settings.theme,
+        view: view::resolve(&view_config),
+        aliases: alias::resolve(&settings.config.aliases, &settings.config.website),
+    };
+    if options.show_attempts {
+        settings.config.leetcode.fetch_attempts = true;
+    }
+    if let Some(top_global) = matches.value_of("top_global") {
+        settings.config.leetcode.top_global = match top_global.parse::<u32>() {
+            Ok(top_global) => top_global,
+            Err(_) => {
+                println!(
+                    "[ERROR] Invalid --top-global {}, expected a number",
+                    top_global
+                );
+                std::process::exit(1);
+            }
+        };
+    }
+    if let Some(neighbours) = matches.value_of("neighbours") {
+        settings.config.leetcode.neighbours = match neighbours.parse::<u32>() {
+            Ok(neighbours) => neighbours,
+            Err(_) => {
+                println!(
+                    "[ERROR] Invalid --neighbours {}, expected a number",
+                    neighbours
+                );
+                std::process::exit(1);
+            }
+        };
+    }
+
     match handlers.get(&settings.config.website) {
         Some(handler) => {
-            println!("[INFO] Prparing data, please wait...");
+            // keep stdout parseable for json output
+            if options.format != OutputFormat::Json {
+                println!("[INFO] Prparing data, please wait...");
+            }
 
-            if is_live {
+            if let Some(compare_matches) = matches.subcommand_matches("compare") {
+                let website =
+                    (handler.new)(is_verbose, settings.config.clone(), runtime.clone(), false);
+                let website_contests = website.render();
+                let render_object = convert_website_object(website_contests, false);
+
+                let users: Vec<&str> = compare_matches
+                    .values_of("users")
+                    .map(|users| users.collect())
+                    .unwrap_or_default();
+                if users.len() == 2 {
+                    render::render_head_to_head(
+                        &compare::head_to_head(&render_object, users[0], users[1]),
+                        &options,
+                    );
+                }
+                if users.len() != 2 || compare_matches.is_present("matrix") {
+                    render::render_matrix(&compare::matrix(&render_object), &options);
+                }
+            } else if let Some(chart_matches) = matches.subcommand_matches("chart") {
+                let height = match chart_matches.value_of("height") {
+                    Some(height) => match height.parse::<usize>() {
+                        Ok(height) => height,
+                        Err(_) => {
+                            println!("[ERROR] Invalid --height {}, expected a number", height);
+                            std::process::exit(1);
+                        }
+                    },
+                    None => 10,
+                };
+                let website =
+                    (handler.new)(is_verbose, settings.config.clone(), runtime.clone(), false);
+
+                let svg_path = chart_matches.value_of("svg");
+
+                let svg = match chart_matches.value_of("kind") {
+                    Some("rating") => {
+                        let mut render_object =
+                            convert_website_object(website.render_history(0), false);
+                        // ratings come from user histories, keep configured contests only
+                        let contests = &settings.config.leetcode.contests;
+                        if !contests.is_empty() {
+                            render_object
+                                .data
+                                .retain(|contest| contests.contains(&contest.id));
+                        }
+
+                        if svg_path.is_some() {
+                            let (labels, series) = chart::rating_series(&render_object, &options);
+                            svg::line_chart("Contest rating", &labels, &series, false, true)
+                        } else {
+                            chart::render_rating_chart(&render_object, height, &options);
+                            None
+                        }
+                    }
+                    Some("times") => {
+                        let render_object = convert_website_object(website.render(), false);
+                        let contest = match chart_matches.value_of("contest") {
+                            Some(id) => render_object.data.iter().find(|contest| contest.id == id),
+                            None => render_object.data.iter().max_by_key(|contest| contest.date),
+                        };
+
+                        match contest {
+                            Some(contest) if svg_path.is_some() => {
+                                svg::contest_timeline(contest, &options)
+                            }
+                            Some(contest) => {
+                                chart::render_time_chart(contest, &options);
+                                None
+                            }
+                            None => {
+                                println!("[ERROR] No such contest in config");
+                                None
+                            }
+                        }
+                    }
+                    _ => {
+                        let render_object = convert_website_object(website.render(), false);
+
+                        if svg_path.is_some() {
+                            let (labels, series) = chart::rank_series(&render_object, &options);
+                            svg::line_chart("Global rank", &labels, &series, true, false)
+                        } else {
+                            chart::render_rank_chart(&render_object, height, &options);
+                            None
+                        }
+                    }
+                };
+
+                if let Some(path) = svg_path {
+                    match svg {
+                        Some(svg) => match fs::write(path, svg) {
+                            Ok(_) => println!("[INFO] 🖼 Chart written to path={}", path),
+                            Err(err) => {
+                                println!("[ERROR] When writing chart, path={}, e={}", path, err)
+                            }
+                        },
+                        None => println!("[WARN] Nothing to chart, no file written"),
+                    }
+                }
+            } else if let Some(country_matches) = matches.subcommand_matches("country") {
+                let country = country_matches.value_of("country").unwrap();
+                let top = match country_matches.value_of("top") {
+                    Some(top) => match top.parse::<usize>() {
+                        Ok(top) => top,
+                        Err(_) => {
+                            println!("[ERROR] Invalid --top {}, expected a number", top);
+                            std::process::exit(1);
+                        }
+                    },
+                    None => 10,
+                };
+                let contest_id = match country_matches.value_of("contest") {
+                    Some(contest_id) => contest_id.to_string(),
+                    None => settings
+                        .config
+                        .leetcode
+                        .contests
+                        .last()
+                        .cloned()
+                        .unwrap_or_default(),
+                };
+                let website =
+                    (handler.new)(is_verbose, settings.config.clone(), runtime.clone(), false);
+
+                match website.render_country(&contest_id, country, top) {
+                    Some(contest) if contest.players.is_empty() => {
+                        println!("[WARN] No participant found, country={}", country);
+                    }
+                    Some(contest) => {
+                        render::output(convert_website_object(vec![contest], false), &options);
+                    }
+                    None => {}
+                }
+            } else if is_history {
+                let website =
+                    (handler.new)(is_verbose, settings.config.clone(), runtime.clone(), false);
+                let website_contests = website.render_history(history_last);
+                let render_object = convert_website_object(website_contests, false);
+                render::render_timeline(render_object, &options);
+            } else if is_live {
                 let website =
                     (handler.new)(is_verbose, settings.config.clone(), runtime.clone(), true);
-                live(&settings.config.live, &website, hide_submission);
+                live(&settings.config.live, &website, &options);
             } else {
                 let website =
                     (handler.new)(is_verbose, settings.config.clone(), runtime.clone(), false);
                 let website_contests = website.render();
-                let render_object = convert_website_object(website_contests, is_live);
-                render::render(render_object, hide_submission);
+                let mut render_object = convert_website_object(website_contests, is_live);
+                stats::fill_stats(&mut render_object, settings.config.trend_window);
+                render_object.countries = country::aggregate(&render_object, &options.view);
+                render_object.teams = group::standings(
+                    &render_object,
+                    &settings.config.groups,
+                    settings.config.team_top,
+                );
+                render_object.seasons = settings
+                    .config
+                    .seasons
+                    .iter()
+                    .map(|season| season::standings(&render_object, season, &settings.date))
+                    .collect();
+                render::output(render_object, &options);
             }
         }
         None => {
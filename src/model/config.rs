@@ -1,11 +1,134 @@
+use crate::{model::render::Theme, utils::date::DateFormatter};
 use serde::{Deserialize, Serialize};
-use std::fs;
+use std::{collections::BTreeMap, error::Error, fmt, fs, path::Path};
+
+// bump together with a new step in service::config migrations
+pub const CONFIG_VERSION: u32 = 1;
+
+const DEFAULT_USER_AGENT: &'static str = "Mozilla/5.0 (Macintosh; Intel Mac OS X 11_2_0) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/88.0.4324.146 Safari/537.36";
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Config {
+    // schema version, files without it are the original format
+    #[serde(default)]
+    pub version: u32,
     pub website: String,
     pub leetcode: WebsiteConfig,
     pub live: LiveConfig,
+    #[serde(default)]
+    pub seasons: Vec<SeasonConfig>,
+    // how many recent contests the improvement trend looks at
+    #[serde(default = "default_trend_window")]
+    pub trend_window: usize,
+    #[serde(default)]
+    pub date: DateConfig,
+    // emoji, ascii, color or auto
+    #[serde(default = "default_theme")]
+    pub theme: String,
+    // named text layouts picked with --view, "default" applies without it
+    #[serde(default)]
+    pub views: BTreeMap<String, ViewConfig>,
+    // team name => members, ranked against each other in team standings
+    #[serde(default)]
+    pub groups: BTreeMap<String, Vec<String>>,
+    // best member scores summed into a team score per contest
+    #[serde(default = "default_team_top")]
+    pub team_top: usize,
+    // "username" applies on every website, "website:username" only there
+    // and wins over the bare username
+    #[serde(default)]
+    pub aliases: BTreeMap<String, AliasConfig>,
+    // named friend circles, the top level settings are the "default" one
+    #[serde(default)]
+    pub profiles: BTreeMap<String, ProfileConfig>,
+    // profile used without --profile, empty means default
+    #[serde(default)]
+    pub current_profile: String,
+}
+
+// lists replace the top level ones while the profile is active, unset
+// render preferences are shared with the top level
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ProfileConfig {
+    pub users: Vec<String>,
+    pub contests: Vec<String>,
+    pub live_users: Vec<String>,
+    pub live_contests: Vec<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<DateConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub views: Option<BTreeMap<String, ViewConfig>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub groups: Option<BTreeMap<String, Vec<String>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seasons: Option<Vec<SeasonConfig>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct AliasConfig {
+    // empty keeps the username
+    pub name: String,
+    // shown before the name everywhere
+    pub emoji: String,
+    // image url, only markdown can show it
+    pub avatar: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct ViewConfig {
+    // any of country, rank, score, time, ak, in display order
+    pub columns: Vec<String>,
+    // rank, score, time, global_rank, name or country
+    pub sort: String,
+    // empty means no filter
+    pub users: Vec<String>,
+    pub countries: Vec<String>,
+    // players shown per contest, 0 shows everyone
+    pub top: usize,
+}
+
+impl Default for ViewConfig {
+    fn default() -> Self {
+        ViewConfig {
+            columns: vec![
+                String::from("time"),
+                String::from("rank"),
+                String::from("score"),
+                String::from("ak"),
+            ],
+            sort: String::from("rank"),
+            users: vec![],
+            countries: vec![],
+            top: 0,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct DateConfig {
+    // e.g. en_US, de_DE
+    pub locale: String,
+    // IANA name like Europe/Berlin, empty means the system timezone
+    pub timezone: String,
+    // strftime style, see chrono::format::strftime
+    pub format: String,
+}
+
+impl Default for DateConfig {
+    fn default() -> Self {
+        DateConfig {
+            locale: String::from("ja_JP"),
+            timezone: String::from(""),
+            format: String::from("%Y-%m-%d %a %H:%M:%S"),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -14,10 +137,75 @@ pub struct LiveConfig {
     pub last: i64,
 }
 
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct SeasonConfig {
+    pub name: String,
+    // contest ids in the season, empty means every configured contest
+    pub contests: Vec<String>,
+    // inclusive local dates as YYYY-MM-DD, empty means unbounded
+    pub start: String,
+    pub end: String,
+    // points for local rank 1, 2, 3..., ranks beyond the table score 0
+    pub points: Vec<u32>,
+    // only the best N results count, 0 counts all of them
+    pub best_of: u32,
+    // extra points for every contest attended, not affected by best_of
+    pub participation_bonus: u32,
+}
+
+impl Default for SeasonConfig {
+    fn default() -> Self {
+        SeasonConfig {
+            name: String::from(""),
+            contests: vec![],
+            start: String::from(""),
+            end: String::from(""),
+            points: vec![25, 18, 15, 12, 10, 8, 6, 4, 2, 1],
+            best_of: 0,
+            participation_bonus: 0,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Settings {
     pub config: Config,
     pub verbose: bool,
+    // config.date with command line overrides applied
+    #[serde(skip)]
+    pub date: DateFormatter,
+    // config.theme with --theme applied
+    #[serde(skip)]
+    pub theme: Theme,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ConfigFormat {
+    Json,
+    Toml,
+    Yaml,
+}
+
+impl ConfigFormat {
+    // picked by extension, anything unknown is json
+    pub fn from_path(path: &str) -> Self {
+        match Path::new(path).extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => return ConfigFormat::Toml,
+            Some("yaml") | Some("yml") => return ConfigFormat::Yaml,
+            _ => return ConfigFormat::Json,
+        }
+    }
+}
+
+impl fmt::Display for ConfigFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigFormat::Json => return write!(f, "json"),
+            ConfigFormat::Toml => return write!(f, "toml"),
+            ConfigFormat::Yaml => return write!(f, "yaml"),
+        }
+    }
 }
 
 impl Config {
@@ -25,23 +213,34 @@ impl Config {
         return serde_json::to_string(self).unwrap();
     }
 
+    fn serialize(&self, format: ConfigFormat) -> Result<String, Box<dyn Error>> {
+        match format {
+            ConfigFormat::Json => return Ok(serde_json::to_string(self)?),
+            // going through a value puts plain keys before tables as toml needs
+            ConfigFormat::Toml => return Ok(toml::to_string(&toml::Value::try_from(self)?)?),
+            ConfigFormat::Yaml => return Ok(serde_yaml::to_string(self)?),
+        }
+    }
+
     pub fn write_to_file(&self, path: &str) -> bool {
-        match fs::File::create(path) {
-            Ok(file) => match serde_json::to_writer(file, self) {
-                Ok(_) => {
-                    return true;
-                }
-                Err(err) => {
-                    format!(
-                        "[ERROR] paring config file error, path={}, err={}",
-                        path, err
-                    );
-                    return false;
-                }
-            },
+        let text = match self.serialize(ConfigFormat::from_path(path)) {
+            Ok(text) => text,
             Err(err) => {
-                format!(
-                    "[ERROR] creating config file error, path={}, err={}",
+                println!(
+                    "[ERROR] serializing config error, path={}, err={}",
+                    path, err
+                );
+                return false;
+            }
+        };
+
+        match fs::write(path, text) {
+            Ok(_) => {
+                return true;
+            }
+            Err(err) => {
+                println!(
+                    "[ERROR] writing config file error, path={}, err={}",
                     path, err
                 );
                 return false;
@@ -61,4 +260,106 @@ pub struct WebsiteConfig {
     pub max_rank: u32,
     pub concurrent: u32,
     pub cache: bool,
+    // look up user histories first to skip contests they didn't enter
+    #[serde(default = "default_true")]
+    pub check_participation: bool,
+    // fetch language and every attempt of the watched users, costs one
+    // request per user per contest
+    #[serde(default)]
+    pub fetch_attempts: bool,
+    // also show the global top and the places around each watched user,
+    // read from the ranking pages and never counted as friends
+    #[serde(default)]
+    pub top_global: u32,
+    #[serde(default)]
+    pub neighbours: u32,
+
+    #[serde(default)]
+    pub request: RequestConfig,
+}
+
+fn default_true() -> bool {
+    return true;
+}
+
+fn default_trend_window() -> usize {
+    return 5;
+}
+
+fn default_team_top() -> usize {
+    return 3;
+}
+
+fn default_theme() -> String {
+    return String::from("auto");
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct RequestConfig {
+    // max requests per second, burst is the bucket capacity
+    pub rate_limit: u32,
+    pub burst: u32,
+    // seconds
+    pub timeout: u64,
+    pub max_retry: u32,
+    // milliseconds
+    pub backoff_base: u64,
+    pub backoff_max: u64,
+
+    // runtime only, set by --record / --replay and never written back
+    #[serde(skip)]
+    pub fixture_mode: FixtureMode,
+    #[serde(skip)]
+    pub fixture_dir: String,
+    // runtime only, directory of the config file
+    #[serde(skip)]
+    pub config_dir: String,
+
+    // empty means the system proxy from HTTP_PROXY / HTTPS_PROXY
+    pub proxy: String,
+    pub user_agent: String,
+    pub headers: BTreeMap<String, String>,
+    // cookies and tokens live here instead of the config file, a relative
+    // path is resolved against the config file's directory
+    pub secrets_file: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct Credentials {
+    // raw Cookie header, e.g. "LEETCODE_SESSION=...; csrftoken=..."
+    pub cookie: String,
+    pub csrf_token: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum FixtureMode {
+    #[default]
+    Off,
+    // save every response into fixture_dir
+    Record,
+    // serve responses from fixture_dir, never touch the network
+    Replay,
+}
+
+impl Default for RequestConfig {
+    fn default() -> Self {
+        RequestConfig {
+            rate_limit: 4,
+            burst: 8,
+            timeout: 15,
+            max_retry: 3,
+            backoff_base: 500,
+            backoff_max: 30000,
+            fixture_mode: FixtureMode::Off,
+            fixture_dir: String::from(""),
+            config_dir: String::from(""),
+            proxy: String::from(""),
+            user_agent: String::from(DEFAULT_USER_AGENT),
+            headers: BTreeMap::new(),
+            secrets_file: String::from("./secrets.json"),
+        }
+    }
 }
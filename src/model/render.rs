@@ -1,20 +1,48 @@
+use crate::{model::config::AliasConfig, utils::date::DateFormatter};
+use serde::Serialize;
+use std::collections::HashMap;
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum SubmissionStatus {
     Accepted,
     Unaccepted,
-    Pending
+    Pending,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize)]
+pub struct Problem {
+    pub title: String,
+    pub url: String,
+    // Easy / Medium / Hard, empty when unknown
+    pub difficulty: String,
+    pub credit: u32,
+}
+
+#[derive(Clone, Serialize)]
+pub struct Attempt {
+    // seconds since contest start
+    pub time: i64,
+    pub verdict: String,
+    pub language: String,
+}
+
+#[derive(Clone, Serialize)]
 pub struct Submission {
     pub fail_count: u32,
     pub finish_time: i64,
     pub status: SubmissionStatus,
     pub score: u32,
+    // short label, T1..T4
     pub title: String,
+    pub problem: Problem,
+    // empty when unknown
+    pub language: String,
+    // every attempt in the contest, only filled when attempts are fetched
+    pub attempts: Vec<Attempt>,
 }
 
+#[derive(Serialize)]
 pub struct User {
     pub username: String,
     pub country: String,
@@ -22,10 +50,27 @@ pub struct User {
     pub global_rank: u32,
     pub score: u32,
     pub local_rank: u32,
+    pub rating: Option<f64>,
+    pub problems_solved: u32,
+    pub total_problems: u32,
+    pub attended: bool,
+    // non-friends are only shown, they get no local rank and no aggregate
+    pub is_friend: bool,
+    // configured alias, only filled for json output
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alias: Option<AliasConfig>,
 
     pub submissions: Vec<Submission>,
 }
 
+#[derive(Serialize)]
+pub struct ContestPercentile {
+    pub name: String,
+    // global rank over participant count, lower is better
+    pub percentile: f64,
+}
+
+#[derive(Serialize)]
 pub struct UserAggregate {
     pub username: String,
     pub country: String,
@@ -34,19 +79,202 @@ pub struct UserAggregate {
     pub attend_count: u32,
     pub total_score: u32,
     pub total_time: i64,
+
+    // longest runs of consecutive contests, a missed contest breaks them
+    pub ak_streak: u32,
+    pub win_streak: u32,
+    pub bug_free_streak: u32,
+    pub avg_percentile: Option<f64>,
+    pub best_contest: Option<ContestPercentile>,
+    pub worst_contest: Option<ContestPercentile>,
+    // percentile points gained per contest over the last contests
+    pub trend: Option<f64>,
+    // configured alias, only filled for json output
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alias: Option<AliasConfig>,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize)]
 pub struct Contest<T = User> {
+    // short id as written in the config, e.g. w227
+    pub id: String,
     pub name: String,
     pub date: i64,
+    // everyone who entered the contest, 0 when unknown
+    pub participant_count: u64,
 
     pub players: Vec<T>,
 }
 
+// per-question summary among the watched users of one contest
+pub struct QuestionStats {
+    pub title: String,
+    pub problem: Problem,
+    pub attend_count: u32,
+    pub solve_count: u32,
+    pub first_solver: Option<String>,
+    pub fastest_time: Option<i64>,
+    pub median_time: Option<i64>,
+    // wrong attempts before accepted, over solvers
+    pub avg_fail_count: f64,
+    pub bug_free_rate: f64,
+}
+
+pub struct QuestionDuel {
+    pub title: String,
+    // None when neither solved it
+    pub winner: Option<String>,
+    // first user minus second user, only when both solved it
+    pub time_diff: Option<i64>,
+}
+
+pub struct ContestDuel {
+    pub name: String,
+    pub date: i64,
+    // local ranks, None when the user didn't participate
+    pub rank: (Option<u32>, Option<u32>),
+    // None unless both participated
+    pub winner: Option<String>,
+    pub score_diff: i64,
+    pub time_diff: i64,
+    pub questions: Vec<QuestionDuel>,
+}
+
+pub struct HeadToHead {
+    pub users: (String, String),
+    pub contests: Vec<ContestDuel>,
+    pub wins: (u32, u32),
+    pub question_wins: (u32, u32),
+}
+
+// wins[i][j] is how many times users[i] finished ahead of users[j]
+pub struct HeadToHeadMatrix {
+    pub users: Vec<String>,
+    pub wins: Vec<Vec<u32>>,
+}
+
+#[derive(Serialize)]
 pub struct RenderObject {
     pub data: Vec<Contest>,
     pub aggregate: Vec<UserAggregate>,
+    pub seasons: Vec<Season>,
+    pub countries: Vec<CountryAggregate>,
+    // None when no groups are configured
+    pub teams: Option<TeamStandings>,
 
     pub is_live: bool,
 }
+
+// one team in one contest, or summed over every contest
+#[derive(Serialize)]
+pub struct TeamResult {
+    pub name: String,
+    // sum of the best `team_top` member scores
+    pub score: u32,
+    // local rank of the best placed member
+    pub best_rank: Option<u32>,
+    pub best_member: Option<String>,
+    // member entries, summed over contests for the overall standings
+    pub attend_count: u32,
+    // contests the team finished first, overall standings only
+    pub win_count: u32,
+}
+
+#[derive(Serialize)]
+pub struct TeamStandings {
+    // in the same order as the contests in `data`
+    pub contests: Vec<Vec<TeamResult>>,
+    pub overall: Vec<TeamResult>,
+}
+
+// watched users grouped by the country of their ranking entries
+#[derive(Serialize)]
+pub struct CountryAggregate {
+    pub country: String,
+    pub user_count: u32,
+    pub attend_count: u32,
+    // the user placed highest in the overall standings
+    pub best_username: String,
+    pub best_total_score: u32,
+}
+
+#[derive(Serialize)]
+pub struct SeasonStanding {
+    pub username: String,
+    pub points: u32,
+    // points left out by best-of counting
+    pub dropped: u32,
+    pub attend_count: u32,
+    pub win_count: u32,
+}
+
+#[derive(Serialize)]
+pub struct Season {
+    pub name: String,
+    pub contest_count: usize,
+    pub best_of: u32,
+    pub standings: Vec<SeasonStanding>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    Text,
+    Markdown,
+    Json,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Theme {
+    Emoji,
+    Ascii,
+    // ASCII glyphs with ANSI colors
+    Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        return Theme::Ascii;
+    }
+}
+
+#[derive(Clone)]
+pub struct RenderOptions {
+    pub format: OutputFormat,
+    pub hide_submission: bool,
+    // show full problem titles instead of T1..T4
+    pub full_title: bool,
+    pub show_attempts: bool,
+    pub date: DateFormatter,
+    pub theme: Theme,
+    pub view: View,
+    // username => alias, already narrowed to the current website
+    pub aliases: HashMap<String, AliasConfig>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Column {
+    Country,
+    GlobalRank,
+    Score,
+    FinishTime,
+    Ak,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum SortKey {
+    LocalRank,
+    Score,
+    FinishTime,
+    GlobalRank,
+    Username,
+    Country,
+}
+
+#[derive(Clone)]
+pub struct View {
+    pub columns: Vec<Column>,
+    pub sort: SortKey,
+    pub users: Vec<String>,
+    pub countries: Vec<String>,
+    pub top: usize,
+}
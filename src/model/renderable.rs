@@ -12,10 +12,25 @@ pub trait Renderable {
     where
         Self: Sized;
     fn render(self: &Self) -> Vec<WebsiteContest>;
+    // contests the watched users attended, built from their profiles; `last`
+    // limits each user to their latest contests, 0 means everything
+    fn render_history(self: &Self, last: usize) -> Vec<WebsiteContest>;
+    // the best `top` participants of one contest from a country, whether
+    // they are watched or not
+    fn render_country(
+        self: &Self,
+        contest_id: &str,
+        country: &str,
+        top: usize,
+    ) -> Option<WebsiteContest>;
 }
 
 pub trait WebsiteTrait {
     fn website_name() -> &'static str;
 
-    fn subcommand_match(website_matches: &ArgMatches, settings: &mut Settings) -> bool;
+    fn subcommand_match(
+        website_matches: &ArgMatches,
+        settings: &mut Settings,
+        runtime: Arc<tokio::runtime::Runtime>,
+    ) -> bool;
 }
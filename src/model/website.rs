@@ -7,6 +7,14 @@ pub struct WebsiteUser {
     pub finish_time: i64,
     pub global_rank: u32,
     pub score: u32,
+    // contest rating after this contest, only known from user history
+    pub rating: Option<f64>,
+    pub problems_solved: u32,
+    pub total_problems: u32,
+    // false for watched users known to have skipped the contest
+    pub attended: bool,
+    // false for global top rankers and rank neighbours shown alongside
+    pub is_friend: bool,
 
     pub submissions: Vec<Submission>,
 }
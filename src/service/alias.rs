@@ -0,0 +1,45 @@
+use crate::model::{config::AliasConfig, render::RenderOptions};
+use std::collections::{BTreeMap, HashMap};
+
+// aliases that apply on `website`, keyed by bare username
+pub fn resolve(
+    aliases: &BTreeMap<String, AliasConfig>,
+    website: &str,
+) -> HashMap<String, AliasConfig> {
+    let mut resolved = HashMap::<String, AliasConfig>::new();
+
+    // bare usernames first so website specific ones override them
+    for (key, alias) in aliases.iter() {
+        if !key.contains(':') {
+            resolved.insert(key.clone(), alias.clone());
+        }
+    }
+    for (key, alias) in aliases.iter() {
+        if let Some(idx) = key.find(':') {
+            if &key[..idx] == website {
+                resolved.insert(key[idx + 1..].to_string(), alias.clone());
+            }
+        }
+    }
+    return resolved;
+}
+
+impl RenderOptions {
+    // what every renderer prints in place of a username
+    pub fn display(&self, username: &str) -> String {
+        let alias = match self.aliases.get(username) {
+            Some(alias) => alias,
+            None => return username.to_string(),
+        };
+
+        let name = if alias.name.is_empty() {
+            username
+        } else {
+            &alias.name
+        };
+        if alias.emoji.is_empty() {
+            return name.to_string();
+        }
+        return format!("{}{}", alias.emoji, name);
+    }
+}
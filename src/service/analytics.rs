@@ -0,0 +1,75 @@
+use crate::model::render::{Contest, QuestionStats, SubmissionStatus};
+
+fn median(times: &[i64]) -> Option<i64> {
+    if times.is_empty() {
+        return None;
+    }
+
+    let mid = times.len() / 2;
+    if times.len() % 2 == 0 {
+        return Some((times[mid - 1] + times[mid]) / 2);
+    }
+    return Some(times[mid]);
+}
+
+// empty when the contest carries no submission detail, e.g. history mode
+pub fn question_stats(contest: &Contest) -> Vec<QuestionStats> {
+    let players: Vec<_> = contest
+        .players
+        .iter()
+        .filter(|player| player.attended && player.is_friend && !player.submissions.is_empty())
+        .collect();
+    if players.is_empty() {
+        return vec![];
+    }
+
+    let mut stats = vec![];
+    for (idx, question) in players[0].submissions.iter().enumerate() {
+        let mut solvers = vec![];
+        for player in players.iter() {
+            if let Some(submission) = player.submissions.get(idx) {
+                if submission.status == SubmissionStatus::Accepted {
+                    solvers.push((player.username.clone(), submission));
+                }
+            }
+        }
+        solvers.sort_by_key(|(_, submission)| submission.finish_time);
+
+        let mut times: Vec<i64> = solvers
+            .iter()
+            .map(|(_, submission)| submission.finish_time)
+            .collect();
+        times.sort();
+
+        let solve_count = solvers.len() as u32;
+        let total_fail_count: u32 = solvers
+            .iter()
+            .map(|(_, submission)| submission.fail_count)
+            .sum();
+        let bug_free_count = solvers
+            .iter()
+            .filter(|(_, submission)| submission.fail_count == 0)
+            .count();
+
+        stats.push(QuestionStats {
+            title: question.title.clone(),
+            problem: question.problem.clone(),
+            attend_count: players.len() as u32,
+            solve_count,
+            first_solver: solvers.first().map(|(username, _)| username.clone()),
+            fastest_time: times.first().cloned(),
+            median_time: median(&times),
+            avg_fail_count: if solve_count > 0 {
+                total_fail_count as f64 / solve_count as f64
+            } else {
+                0.0
+            },
+            bug_free_rate: if solve_count > 0 {
+                bug_free_count as f64 / solve_count as f64
+            } else {
+                0.0
+            },
+        });
+    }
+    return stats;
+}
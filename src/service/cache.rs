@@ -9,7 +9,7 @@ const CACHE_FILE_PATH: &str = "./cache";
 
 macro_rules! cache_path {
     ($name: expr) => {
-        format!("{}/{}.cache", CACHE_FILE_PATH, $name);
+        format!("{}/{}.cache", CACHE_FILE_PATH, $name)
     };
 }
 
@@ -0,0 +1,363 @@
+use crate::{
+    model::render,
+    service::{
+        render::problem_label,
+        theme::{pad, Glyph},
+    },
+    utils::finish_time,
+};
+
+// drawing characters, the plain themes stay pure ASCII
+struct Charset {
+    markers: [char; 8],
+    sparks: [char; 8],
+    collision: char,
+    bar: &'static str,
+    axis: &'static str,
+    corner: &'static str,
+    rule: &'static str,
+}
+
+const UNICODE: Charset = Charset {
+    markers: ['●', '▲', '■', '◆', '★', '✚', '○', '△'],
+    sparks: ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'],
+    collision: '✱',
+    bar: "█",
+    axis: "│",
+    corner: "└─",
+    rule: "─",
+};
+
+const ASCII: Charset = Charset {
+    markers: ['o', '^', '#', '@', '*', '+', '%', 'v'],
+    sparks: ['.', ',', ':', '-', '=', '+', '*', '#'],
+    collision: 'X',
+    bar: "#",
+    axis: "|",
+    corner: "+-",
+    rule: "-",
+};
+const COLUMN_WIDTH: usize = 6;
+const BAR_WIDTH: f64 = 40.0;
+
+fn charset(theme: render::Theme) -> &'static Charset {
+    match theme {
+        render::Theme::Emoji => return &UNICODE,
+        render::Theme::Ascii | render::Theme::Color => return &ASCII,
+    }
+}
+
+pub struct Series {
+    pub username: String,
+    pub values: Vec<Option<f64>>,
+}
+
+pub struct Scale {
+    min: f64,
+    max: f64,
+    log: bool,
+    // rating grows upwards, rank is better when smaller
+    higher_is_better: bool,
+}
+
+impl Scale {
+    pub fn new(series: &[Series], log: bool, higher_is_better: bool) -> Option<Self> {
+        let values: Vec<f64> = series
+            .iter()
+            .flat_map(|series| series.values.iter())
+            .filter_map(|value| *value)
+            .filter(|value| !log || *value > 0.0)
+            .map(|value| if log { value.ln() } else { value })
+            .collect();
+        if values.is_empty() {
+            return None;
+        }
+
+        let mut min = values.iter().cloned().fold(f64::INFINITY, f64::min);
+        let mut max = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        if (max - min).abs() < f64::EPSILON {
+            min -= 0.5;
+            max += 0.5;
+        }
+        return Some(Scale {
+            min,
+            max,
+            log,
+            higher_is_better,
+        });
+    }
+
+    // 0.0 is the worst value on the chart, 1.0 the best
+    pub fn fraction(&self, value: f64) -> f64 {
+        let value = if self.log { value.ln() } else { value };
+        let fraction = (value - self.min) / (self.max - self.min);
+        if self.higher_is_better {
+            return fraction;
+        }
+        return 1.0 - fraction;
+    }
+
+    pub fn value(&self, fraction: f64) -> f64 {
+        let fraction = if self.higher_is_better {
+            fraction
+        } else {
+            1.0 - fraction
+        };
+        let value = self.min + fraction * (self.max - self.min);
+        if self.log {
+            return value.exp();
+        }
+        return value;
+    }
+}
+
+fn render_sparkline(scale: &Scale, values: &[Option<f64>], sparks: &[char; 8]) -> String {
+    return values
+        .iter()
+        .map(|value| match value {
+            Some(value) if !scale.log || *value > 0.0 => {
+                let idx = (scale.fraction(*value) * (sparks.len() - 1) as f64).round();
+                sparks[idx as usize]
+            }
+            _ => ' ',
+        })
+        .collect();
+}
+
+fn render_line_chart(
+    title: &str,
+    labels: &[String],
+    series: &[Series],
+    height: usize,
+    log: bool,
+    higher_is_better: bool,
+    theme: render::Theme,
+) {
+    let scale = match Scale::new(series, log, higher_is_better) {
+        Some(scale) => scale,
+        None => {
+            println!("[INFO] Nothing to chart, no data for {}", title);
+            return;
+        }
+    };
+    let height = height.max(2);
+    let chars = charset(theme);
+
+    let mut grid = vec![vec![' '; labels.len()]; height];
+    for (idx, series) in series.iter().enumerate() {
+        let marker = chars.markers[idx % chars.markers.len()];
+        for (column, value) in series.values.iter().enumerate() {
+            let value = match value {
+                Some(value) if !log || *value > 0.0 => *value,
+                _ => continue,
+            };
+
+            let row = ((1.0 - scale.fraction(value)) * (height - 1) as f64).round() as usize;
+            grid[row][column] = if grid[row][column] == ' ' {
+                marker
+            } else {
+                chars.collision
+            };
+        }
+    }
+
+    println!("{}{}", theme.glyph(Glyph::Chart), title);
+    for (row, cells) in grid.iter().enumerate() {
+        let value = scale.value(1.0 - row as f64 / (height - 1) as f64);
+        let line: String = cells
+            .iter()
+            .map(|cell| format!("{:<width$}", cell, width = COLUMN_WIDTH))
+            .collect();
+        let line = format!("  {:>8.0} {} {}", value, chars.axis, line);
+        println!("{}", line.trim_end());
+    }
+    println!(
+        "  {:>8} {}{}",
+        "",
+        chars.corner,
+        chars.rule.repeat(labels.len() * COLUMN_WIDTH)
+    );
+    let axis: String = labels
+        .iter()
+        .map(|label| format!("{:<width$}", label, width = COLUMN_WIDTH))
+        .collect();
+    println!("  {:>8}   {}", "", axis.trim_end());
+
+    println!();
+    for (idx, series) in series.iter().enumerate() {
+        println!(
+            "  {} {} {}",
+            chars.markers[idx % chars.markers.len()],
+            pad(&series.username, 24),
+            render_sparkline(&scale, &series.values, &chars.sparks)
+        );
+    }
+}
+
+fn contest_labels(contests: &[&render::Contest]) -> Vec<String> {
+    return contests
+        .iter()
+        .enumerate()
+        .map(|(idx, contest)| {
+            if contest.id.is_empty() {
+                return format!("#{}", idx + 1);
+            }
+            return contest.id.clone();
+        })
+        .collect();
+}
+
+fn user_series<F>(
+    object: &render::RenderObject,
+    options: &render::RenderOptions,
+    value: F,
+) -> (Vec<String>, Vec<Series>)
+where
+    F: Fn(&render::User) -> Option<f64>,
+{
+    let mut contests: Vec<&render::Contest> = object.data.iter().collect();
+    contests.sort_by(|lhs, rhs| lhs.date.cmp(&rhs.date));
+
+    let series = object
+        .aggregate
+        .iter()
+        .map(|aggregate| Series {
+            username: options.display(&aggregate.username),
+            values: contests
+                .iter()
+                .map(|contest| {
+                    contest
+                        .players
+                        .iter()
+                        .find(|player| player.username == aggregate.username && player.attended)
+                        .and_then(|player| value(player))
+                })
+                .collect(),
+        })
+        .collect();
+    return (contest_labels(&contests), series);
+}
+
+pub fn rank_series(
+    object: &render::RenderObject,
+    options: &render::RenderOptions,
+) -> (Vec<String>, Vec<Series>) {
+    return user_series(object, options, |player| {
+        if player.global_rank == 0 {
+            return None;
+        }
+        return Some(player.global_rank as f64);
+    });
+}
+
+pub fn rating_series(
+    object: &render::RenderObject,
+    options: &render::RenderOptions,
+) -> (Vec<String>, Vec<Series>) {
+    return user_series(object, options, |player| player.rating);
+}
+
+pub fn render_rank_chart(
+    object: &render::RenderObject,
+    height: usize,
+    options: &render::RenderOptions,
+) {
+    let (labels, series) = rank_series(object, options);
+    render_line_chart(
+        "Global rank (log scale)",
+        &labels,
+        &series,
+        height,
+        true,
+        false,
+        options.theme,
+    );
+}
+
+pub fn render_rating_chart(
+    object: &render::RenderObject,
+    height: usize,
+    options: &render::RenderOptions,
+) {
+    let (labels, series) = rating_series(object, options);
+    render_line_chart(
+        "Contest rating",
+        &labels,
+        &series,
+        height,
+        false,
+        true,
+        options.theme,
+    );
+}
+
+pub fn render_time_chart(contest: &render::Contest, options: &render::RenderOptions) {
+    let players: Vec<&render::User> = contest
+        .players
+        .iter()
+        .filter(|player| player.attended && player.is_friend)
+        .collect();
+    let questions = match players
+        .iter()
+        .map(|player| &player.submissions)
+        .find(|submissions| !submissions.is_empty())
+    {
+        Some(questions) => questions,
+        None => {
+            println!(
+                "[INFO] Nothing to chart, no submissions in {}",
+                contest.name
+            );
+            return;
+        }
+    };
+
+    let max_time = players
+        .iter()
+        .flat_map(|player| player.submissions.iter())
+        .filter(|submission| submission.status == render::SubmissionStatus::Accepted)
+        .map(|submission| submission.finish_time)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+
+    println!(
+        "{}{} solve times",
+        options.theme.glyph(Glyph::SolveTimes),
+        contest.name
+    );
+    for (tid, question) in questions.iter().enumerate() {
+        println!(
+            "  {}",
+            problem_label(&question.title, &question.problem, options.full_title)
+        );
+
+        for player in players.iter() {
+            let submission = match player.submissions.get(tid) {
+                Some(submission) => submission,
+                None => continue,
+            };
+
+            let bar = match submission.status {
+                render::SubmissionStatus::Accepted => {
+                    let width = (submission.finish_time as f64 / max_time as f64 * BAR_WIDTH)
+                        .round()
+                        .max(1.0);
+                    format!(
+                        "{} {}",
+                        charset(options.theme).bar.repeat(width as usize),
+                        finish_time::seconds_to_finish_time(submission.finish_time)
+                    )
+                }
+                render::SubmissionStatus::Unaccepted => options.theme.glyph(Glyph::Unaccepted),
+                render::SubmissionStatus::Pending => options.theme.glyph(Glyph::Pending),
+            };
+            println!(
+                "    {}{} {}",
+                options.theme.medal(player.local_rank),
+                pad(&options.display(&player.username), 24),
+                bar
+            );
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use crate::model::render::{
+    ContestDuel, HeadToHead, HeadToHeadMatrix, QuestionDuel, RenderObject, SubmissionStatus, User,
+};
+
+fn find_player<'a>(players: &'a [User], username: &str) -> Option<&'a User> {
+    return players
+        .iter()
+        .find(|player| player.attended && player.is_friend && player.username == username);
+}
+
+fn question_duels(lhs: &User, rhs: &User) -> Vec<QuestionDuel> {
+    let mut duels = vec![];
+    for (lhs_submission, rhs_submission) in lhs.submissions.iter().zip(rhs.submissions.iter()) {
+        let lhs_solved = lhs_submission.status == SubmissionStatus::Accepted;
+        let rhs_solved = rhs_submission.status == SubmissionStatus::Accepted;
+
+        let (winner, time_diff) = match (lhs_solved, rhs_solved) {
+            (true, true) => {
+                let diff = lhs_submission.finish_time - rhs_submission.finish_time;
+                if diff <= 0 {
+                    (Some(lhs.username.clone()), Some(diff))
+                } else {
+                    (Some(rhs.username.clone()), Some(diff))
+                }
+            }
+            (true, false) => (Some(lhs.username.clone()), None),
+            (false, true) => (Some(rhs.username.clone()), None),
+            (false, false) => (None, None),
+        };
+
+        duels.push(QuestionDuel {
+            title: lhs_submission.title.clone(),
+            winner,
+            time_diff,
+        });
+    }
+    return duels;
+}
+
+pub fn head_to_head(object: &RenderObject, lhs_name: &str, rhs_name: &str) -> HeadToHead {
+    let mut result = HeadToHead {
+        users: (lhs_name.to_string(), rhs_name.to_string()),
+        contests: vec![],
+        wins: (0, 0),
+        question_wins: (0, 0),
+    };
+
+    for contest in object.data.iter() {
+        let lhs = find_player(&contest.players, lhs_name);
+        let rhs = find_player(&contest.players, rhs_name);
+        if lhs.is_none() && rhs.is_none() {
+            continue;
+        }
+
+        let mut duel = ContestDuel {
+            name: contest.name.clone(),
+            date: contest.date,
+            rank: (
+                lhs.map(|user| user.local_rank),
+                rhs.map(|user| user.local_rank),
+            ),
+            winner: None,
+            score_diff: 0,
+            time_diff: 0,
+            questions: vec![],
+        };
+
+        if let (Some(lhs), Some(rhs)) = (lhs, rhs) {
+            // local_rank already breaks ties the same way the contest does
+            if lhs.local_rank < rhs.local_rank {
+                duel.winner = Some(lhs.username.clone());
+                result.wins.0 += 1;
+            } else {
+                duel.winner = Some(rhs.username.clone());
+                result.wins.1 += 1;
+            }
+
+            duel.score_diff = lhs.score as i64 - rhs.score as i64;
+            duel.time_diff = lhs.finish_time - rhs.finish_time;
+            duel.questions = question_duels(lhs, rhs);
+
+            for question in duel.questions.iter() {
+                match &question.winner {
+                    Some(winner) if winner == lhs_name => result.question_wins.0 += 1,
+                    Some(_) => result.question_wins.1 += 1,
+                    None => {}
+                }
+            }
+        }
+
+        result.contests.push(duel);
+    }
+
+    return result;
+}
+
+pub fn matrix(object: &RenderObject) -> HeadToHeadMatrix {
+    let users: Vec<String> = object
+        .aggregate
+        .iter()
+        .map(|aggregate| aggregate.username.clone())
+        .collect();
+    let mut wins = vec![vec![0u32; users.len()]; users.len()];
+
+    for contest in object.data.iter() {
+        for (i, lhs_name) in users.iter().enumerate() {
+            for (j, rhs_name) in users.iter().enumerate() {
+                if i == j {
+                    continue;
+                }
+
+                let lhs = find_player(&contest.players, lhs_name);
+                let rhs = find_player(&contest.players, rhs_name);
+                if let (Some(lhs), Some(rhs)) = (lhs, rhs) {
+                    if lhs.local_rank < rhs.local_rank {
+                        wins[i][j] += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    return HeadToHeadMatrix { users, wins };
+}
@@ -1,8 +1,19 @@
-use crate::model::config;
-use std::{fs::File, path::Path};
+use crate::{
+    model::config::{self, ConfigFormat, CONFIG_VERSION},
+    service::validate::{self, Severity},
+};
+use serde_json::Value;
+use std::{collections::BTreeMap, env, error::Error, fs, path::Path};
+
+const CONFIG_ENV: &str = "CONTEST_RANK_CONFIG";
+const CONFIG_NAMES: [&str; 4] = ["conf.json", "conf.toml", "conf.yaml", "conf.yml"];
+
+// MIGRATIONS[v] turns a version v file into version v + 1
+const MIGRATIONS: [fn(&mut Value); CONFIG_VERSION as usize] = [migrate_v0];
 
 fn get_default_config() -> config::Config {
     config::Config {
+        version: CONFIG_VERSION,
         leetcode: config::WebsiteConfig {
             users: vec![],
             contests: vec![],
@@ -13,15 +24,154 @@ fn get_default_config() -> config::Config {
             concurrent: 1,
             cache: false,
             max_rank: 2000,
+            check_participation: true,
+            fetch_attempts: false,
+            top_global: 0,
+            neighbours: 0,
+            request: config::RequestConfig::default(),
         },
         website: String::from(""),
         live: config::LiveConfig {
             interval: 600u64,
             last: 5400i64,
         },
+        seasons: vec![],
+        trend_window: 5,
+        date: config::DateConfig::default(),
+        theme: String::from("auto"),
+        views: BTreeMap::new(),
+        groups: BTreeMap::new(),
+        team_top: 3,
+        aliases: BTreeMap::new(),
+        profiles: BTreeMap::new(),
+        current_profile: String::from(""),
     }
 }
 
+// the original format had no version and every key added since then has
+// a serde default, so only the version needs to be written
+fn migrate_v0(_value: &mut Value) {}
+
+pub struct ConfigLocation {
+    pub path: String,
+    // which search step picked the path
+    pub source: String,
+}
+
+// -c, then $CONTEST_RANK_CONFIG, then the first existing file in the XDG
+// config dir and the current dir, a new config goes into the XDG dir
+pub fn locate_config(explicit: Option<&str>) -> ConfigLocation {
+    if let Some(path) = explicit {
+        return ConfigLocation {
+            path: path.to_string(),
+            source: String::from("-c"),
+        };
+    }
+    if let Ok(path) = env::var(CONFIG_ENV) {
+        if !path.is_empty() {
+            return ConfigLocation {
+                path,
+                source: format!("${}", CONFIG_ENV),
+            };
+        }
+    }
+
+    let config_dir = dirs::config_dir().map(|dir| dir.join("contest-rank"));
+    let mut candidates = vec![];
+    if let Some(dir) = &config_dir {
+        for name in CONFIG_NAMES.iter() {
+            candidates.push((dir.join(name), "XDG config dir"));
+        }
+    }
+    for name in CONFIG_NAMES.iter() {
+        candidates.push((Path::new(".").join(name), "current dir"));
+    }
+    for (path, source) in candidates.iter() {
+        if path.exists() {
+            return ConfigLocation {
+                path: path.to_string_lossy().to_string(),
+                source: source.to_string(),
+            };
+        }
+    }
+
+    match config_dir {
+        Some(dir) => {
+            return ConfigLocation {
+                path: dir.join(CONFIG_NAMES[0]).to_string_lossy().to_string(),
+                source: String::from("XDG config dir, new file"),
+            }
+        }
+        None => {
+            return ConfigLocation {
+                path: format!("./{}", CONFIG_NAMES[0]),
+                source: String::from("current dir, new file"),
+            }
+        }
+    }
+}
+
+fn parse_error<E: Error + 'static>(err: serde_path_to_error::Error<E>) -> Box<dyn Error> {
+    let path = err.path().to_string();
+    if path == "." {
+        return Box::new(err.into_inner());
+    }
+    return format!("{}: {}", path, err.into_inner()).into();
+}
+
+// returns the config at CONFIG_VERSION and the version the text was in
+pub fn parse_config(
+    text: &str,
+    format: ConfigFormat,
+) -> Result<(config::Config, u32), Box<dyn Error>> {
+    // migrations work on json values whatever the file format is
+    let mut value: Value = match format {
+        ConfigFormat::Json => serde_json::from_str(text)?,
+        ConfigFormat::Toml => toml::from_str(text)?,
+        ConfigFormat::Yaml => serde_yaml::from_str(text)?,
+    };
+    let version = match value.get("version") {
+        None => 0,
+        Some(version) => match version.as_u64() {
+            Some(version) => version as u32,
+            None => return Err(format!("version: expected a number, got {}", version).into()),
+        },
+    };
+    if version > CONFIG_VERSION {
+        return Err(format!(
+            "version: {} is newer than the supported version {}",
+            version, CONFIG_VERSION
+        )
+        .into());
+    }
+
+    // parse the text itself when nothing changes, so errors keep line numbers
+    if version == CONFIG_VERSION {
+        let config = match format {
+            ConfigFormat::Json => {
+                serde_path_to_error::deserialize(&mut serde_json::Deserializer::from_str(text))
+                    .map_err(parse_error)?
+            }
+            ConfigFormat::Toml => {
+                serde_path_to_error::deserialize(&mut toml::Deserializer::new(text))
+                    .map_err(parse_error)?
+            }
+            ConfigFormat::Yaml => {
+                serde_path_to_error::deserialize(serde_yaml::Deserializer::from_str(text))
+                    .map_err(parse_error)?
+            }
+        };
+        return Ok((config, version));
+    }
+
+    for migrate in MIGRATIONS[version as usize..].iter() {
+        migrate(&mut value);
+    }
+    value["version"] = Value::from(CONFIG_VERSION);
+    let config = serde_path_to_error::deserialize(value).map_err(parse_error)?;
+    return Ok((config, version));
+}
+
 pub fn read_config(path: &str) -> config::Config {
     let default_config = get_default_config();
 
@@ -31,24 +181,99 @@ pub fn read_config(path: &str) -> config::Config {
             "[INFO] Config file doesn't exist, will write to file path={}",
             path
         );
-        
+
+        if let Some(dir) = config_file.parent() {
+            if !dir.as_os_str().is_empty() && fs::create_dir_all(dir).is_err() {
+                println!("[WARN] Can't create config dir, path={}", dir.display());
+            }
+        }
         default_config.write_to_file(path);
         return default_config;
     }
 
-    match File::open(path) {
-        Ok(config_file) => match serde_json::from_reader::<File, config::Config>(config_file) {
-            Ok(current_config) => return current_config,
+    // falling back to defaults would drop every setting, so stop instead
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(err) => {
+            println!("[ERROR] When reading config, path={}, err={}", path, err);
+            std::process::exit(1);
+        }
+    };
+    let (config, version) = match parse_config(&text, ConfigFormat::from_path(path)) {
+        Ok(parsed) => parsed,
+        Err(err) => {
+            println!("[ERROR] When parsing config, path={}, err={}", path, err);
+            std::process::exit(1);
+        }
+    };
+
+    if version < CONFIG_VERSION {
+        let backup = format!("{}.v{}.bak", path, version);
+        match fs::copy(path, &backup) {
+            Ok(_) => {
+                if config.write_to_file(path) {
+                    println!(
+                        "[INFO] 🔧 Config migrated from version {} to {}, backup at path={}",
+                        version, CONFIG_VERSION, backup
+                    );
+                }
+            }
             Err(err) => println!(
-                "[ERROR] When parsing config, use default config instead, err={}",
-                err
+                "[WARN] Config not migrated, backup failed, path={}, err={}",
+                backup, err
             ),
-        },
-        Err(err) => println!(
-            "[ERROR] When reading config, use default config instead, err={}",
-            err
-        ),
+        }
+    }
+
+    return config;
+}
+
+// prints every problem of the config file, true when nothing is an error
+pub fn check_config(path: &str) -> bool {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(err) => {
+            println!("[ERROR] When reading config, path={}, err={}", path, err);
+            return false;
+        }
+    };
+    let (config, version) = match parse_config(&text, ConfigFormat::from_path(path)) {
+        Ok(parsed) => parsed,
+        Err(err) => {
+            println!("[ERROR] {}", err);
+            return false;
+        }
+    };
+    if version < CONFIG_VERSION {
+        println!(
+            "[INFO] Config is version {}, will be migrated to {} on the next run",
+            version, CONFIG_VERSION
+        );
+    }
+
+    let issues = validate::validate(&config);
+    for issue in issues.iter() {
+        issue.print();
+    }
+    let errors = issues
+        .iter()
+        .filter(|issue| issue.severity == Severity::Error)
+        .count();
+    if errors > 0 {
+        println!(
+            "[ERROR] ❌ Config has {} error(s) and {} warning(s), path={}",
+            errors,
+            issues.len() - errors,
+            path
+        );
+        return false;
     }
 
-    return default_config;
+    println!(
+        "[INFO] ✅ Config is valid with {} warning(s), path={}, version={}",
+        issues.len(),
+        path,
+        CONFIG_VERSION
+    );
+    return true;
 }
@@ -24,53 +24,91 @@ pub fn convert_website_object(
                 score: player.score,
                 submissions: player.submissions.to_vec(),
                 local_rank: 0,
+                rating: player.rating,
+                problems_solved: player.problems_solved,
+                total_problems: player.total_problems,
+                attended: player.attended,
+                is_friend: player.is_friend,
+                alias: None,
             });
 
-            match user_hashtable.get(&player.username) {
+            if !player.is_friend {
+                continue;
+            }
+
+            let uid = match user_hashtable.get(&player.username) {
+                Some(uid) => *uid,
                 None => {
-                    let aggregate_obj = UserAggregate {
+                    aggregate.push(UserAggregate {
                         username: player.username.clone(),
                         country: player.country.clone(),
                         win_count: 0,
-                        attend_count: 1,
-                        total_score: player.score,
-                        total_time: player.finish_time,
-                    };
-
-                    aggregate.push(aggregate_obj);
+                        attend_count: 0,
+                        total_score: 0,
+                        total_time: 0,
+                        ak_streak: 0,
+                        win_streak: 0,
+                        bug_free_streak: 0,
+                        avg_percentile: None,
+                        best_contest: None,
+                        worst_contest: None,
+                        trend: None,
+                        alias: None,
+                    });
                     user_hashtable.insert(player.username.clone(), aggregate.len() - 1);
+                    aggregate.len() - 1
                 }
-                Some(uid) => {
-                    let mut user = &mut (aggregate[*uid]);
-                    user.total_score = user.total_score + player.score;
-                    user.attend_count += 1;
-                    user.total_time += player.finish_time;
+            };
+
+            if player.attended {
+                let user = &mut (aggregate[uid]);
+                user.total_score = user.total_score + player.score;
+                user.attend_count += 1;
+                user.total_time += player.finish_time;
+                if user.country.is_empty() {
+                    user.country = player.country.clone();
                 }
             }
         }
 
         players.sort_by(|lhs, rhs| {
+            // absent players always go last
+            if lhs.attended != rhs.attended {
+                return rhs.attended.cmp(&lhs.attended);
+            }
+
             if lhs.score == rhs.score {
+                // global rank already orders by score and penalty, and is the
+                // only usable order for history entries which carry no score
+                if lhs.global_rank > 0 && rhs.global_rank > 0 {
+                    return lhs.global_rank.cmp(&rhs.global_rank);
+                }
                 return lhs.finish_time.cmp(&rhs.finish_time);
             }
 
             return rhs.score.cmp(&lhs.score);
         });
 
-        for i in 0..players.len() {
-            players[i].local_rank = (i + 1) as u32;
+        // non-friends are left out of the local ranking
+        let mut local_rank = 0u32;
+        for player in players.iter_mut() {
+            if player.attended && player.is_friend {
+                local_rank += 1;
+                player.local_rank = local_rank;
+            }
         }
 
         // Winner Count
-        if players.len() > 0 {
-            let winner_username = players[0].username.clone();
-            let uid = user_hashtable.get(&winner_username).unwrap();
+        if let Some(winner) = players.iter().find(|player| player.local_rank == 1) {
+            let uid = user_hashtable.get(&winner.username).unwrap();
             aggregate[*uid].win_count += 1;
         }
 
         data.push(Contest {
+            id: web_contest.id.clone(),
             name: web_contest.name.clone(),
             date: web_contest.date.clone(),
+            participant_count: web_contest.participant_count,
             players,
         });
     }
@@ -85,6 +123,9 @@ pub fn convert_website_object(
     return RenderObject {
         data,
         aggregate,
+        seasons: vec![],
+        countries: vec![],
+        teams: None,
         is_live,
     };
 }
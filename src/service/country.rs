@@ -0,0 +1,37 @@
+use crate::{
+    model::render::{CountryAggregate, RenderObject, View},
+    service::view,
+};
+
+// one entry per country among the watched users the view shows, users
+// without a known country are left out; the most represented go first
+pub fn aggregate(object: &RenderObject, view: &View) -> Vec<CountryAggregate> {
+    let mut countries = Vec::<CountryAggregate>::new();
+
+    // aggregate is already in standings order, the first user seen is the best
+    for user in object.aggregate.iter() {
+        if user.country.is_empty() || !view::is_visible(view, &user.username, &user.country) {
+            continue;
+        }
+
+        match countries
+            .iter_mut()
+            .find(|country| country.country == user.country)
+        {
+            Some(country) => {
+                country.user_count += 1;
+                country.attend_count += user.attend_count;
+            }
+            None => countries.push(CountryAggregate {
+                country: user.country.clone(),
+                user_count: 1,
+                attend_count: user.attend_count,
+                best_username: user.username.clone(),
+                best_total_score: user.total_score,
+            }),
+        }
+    }
+
+    countries.sort_by(|lhs, rhs| rhs.user_count.cmp(&lhs.user_count));
+    return countries;
+}
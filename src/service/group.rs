@@ -0,0 +1,177 @@
+use crate::model::{
+    config::Config,
+    render::{Contest, RenderObject, TeamResult, TeamStandings},
+};
+use clap::ArgMatches;
+use std::{cmp::Ordering, collections::BTreeMap};
+
+// higher score first, then the better best member
+fn compare(lhs: &TeamResult, rhs: &TeamResult) -> Ordering {
+    return rhs.score.cmp(&lhs.score).then(
+        lhs.best_rank
+            .unwrap_or(u32::MAX)
+            .cmp(&rhs.best_rank.unwrap_or(u32::MAX)),
+    );
+}
+
+fn contest_result(contest: &Contest, name: &str, members: &[String], top: usize) -> TeamResult {
+    // players are in local rank order, friends only
+    let players: Vec<_> = contest
+        .players
+        .iter()
+        .filter(|player| player.attended && player.is_friend && members.contains(&player.username))
+        .collect();
+
+    let mut scores: Vec<u32> = players.iter().map(|player| player.score).collect();
+    scores.sort_by(|lhs, rhs| rhs.cmp(lhs));
+
+    return TeamResult {
+        name: name.to_string(),
+        score: scores.iter().take(top).sum(),
+        best_rank: players.first().map(|player| player.local_rank),
+        best_member: players.first().map(|player| player.username.clone()),
+        attend_count: players.len() as u32,
+        win_count: 0,
+    };
+}
+
+pub fn standings(
+    object: &RenderObject,
+    groups: &BTreeMap<String, Vec<String>>,
+    top: usize,
+) -> Option<TeamStandings> {
+    if groups.is_empty() {
+        return None;
+    }
+
+    let mut overall: Vec<TeamResult> = groups
+        .keys()
+        .map(|name| TeamResult {
+            name: name.clone(),
+            score: 0,
+            best_rank: None,
+            best_member: None,
+            attend_count: 0,
+            win_count: 0,
+        })
+        .collect();
+
+    let mut contests = vec![];
+    for contest in object.data.iter() {
+        let mut results: Vec<TeamResult> = groups
+            .iter()
+            .map(|(name, members)| contest_result(contest, name, members, top))
+            .collect();
+        results.sort_by(compare);
+
+        for (idx, result) in results.iter().enumerate() {
+            let total = overall
+                .iter_mut()
+                .find(|total| total.name == result.name)
+                .unwrap();
+            total.score += result.score;
+            total.attend_count += result.attend_count;
+            if idx == 0 && result.attend_count > 0 {
+                total.win_count += 1;
+            }
+            if let Some(rank) = result.best_rank {
+                if total.best_rank.map_or(true, |best| rank < best) {
+                    total.best_rank = Some(rank);
+                }
+            }
+        }
+        contests.push(results);
+    }
+
+    // the best member overall is the first one in the individual standings
+    for total in overall.iter_mut() {
+        let members = &groups[&total.name];
+        total.best_member = object
+            .aggregate
+            .iter()
+            .find(|user| user.attend_count > 0 && members.contains(&user.username))
+            .map(|user| user.username.clone());
+    }
+    overall.sort_by(|lhs, rhs| {
+        if lhs.score == rhs.score {
+            return rhs.win_count.cmp(&lhs.win_count).then(compare(lhs, rhs));
+        }
+        return rhs.score.cmp(&lhs.score);
+    });
+
+    return Some(TeamStandings { contests, overall });
+}
+
+pub fn subcommand_match(group_matches: &ArgMatches, config: &mut Config) -> bool {
+    match group_matches.subcommand() {
+        ("add", Some(arg_matches)) => {
+            let name = arg_matches.value_of("name").unwrap();
+            let members = config.groups.entry(name.to_string()).or_insert(vec![]);
+
+            let mut changed = false;
+            for username in arg_matches.values_of("users").unwrap() {
+                if members.iter().any(|member| member == username) {
+                    println!("[INFO] ❌ User {} already in group {}", username, name);
+                    continue;
+                }
+                if !config.leetcode.users.iter().any(|user| user == username) {
+                    println!(
+                        "[WARN] User {} isn't watched, the team won't score for them",
+                        username
+                    );
+                }
+
+                members.push(username.to_string());
+                println!("[INFO] 🔧 Added user {} to group {}", username, name);
+                changed = true;
+            }
+            return changed;
+        }
+        ("delete", Some(arg_matches)) => {
+            let name = arg_matches.value_of("name").unwrap();
+            let members = match config.groups.get_mut(name) {
+                Some(members) => members,
+                None => {
+                    println!("[INFO] ❌ Group {} doesn't exist", name);
+                    return false;
+                }
+            };
+
+            match arg_matches.values_of("users") {
+                Some(usernames) => {
+                    let mut changed = false;
+                    for username in usernames {
+                        match members.iter().position(|member| member == username) {
+                            Some(idx) => {
+                                members.remove(idx);
+                                println!("[INFO] 🔧 Removed user {} from group {}", username, name);
+                                changed = true;
+                            }
+                            None => {
+                                println!("[INFO] ❌ User {} isn't in group {}", username, name)
+                            }
+                        }
+                    }
+                    return changed;
+                }
+                None => {
+                    config.groups.remove(name);
+                    println!("[INFO] 🔧 Removed group {}", name);
+                    return true;
+                }
+            }
+        }
+        ("list", _) => {
+            if config.groups.is_empty() {
+                println!("[INFO] No group configured");
+            }
+            for (name, members) in config.groups.iter() {
+                println!("👥{} ({}): {}", name, members.len(), members.join(", "));
+            }
+            return false;
+        }
+        _ => {}
+    }
+
+    return false;
+}
@@ -9,7 +9,8 @@ use std::{collections::HashMap, sync::Arc};
 
 pub struct HandlerHashMapValue {
     pub new: fn(bool, config::Config, Arc<tokio::runtime::Runtime>, bool) -> Box<dyn Renderable>,
-    pub subcommand_match: fn(&clap::ArgMatches, &mut config::Settings) -> bool,
+    pub subcommand_match:
+        fn(&clap::ArgMatches, &mut config::Settings, Arc<tokio::runtime::Runtime>) -> bool,
 }
 
 type HandlerHashMapType = HashMap<String, HandlerHashMapValue>;
@@ -0,0 +1,386 @@
+use crate::{
+    model::render,
+    service::{
+        analytics,
+        render::{
+            problem_label, render_optional_time, render_percentile, render_season_title,
+            render_trend,
+        },
+        theme::Glyph,
+    },
+    utils::finish_time,
+};
+
+fn escape(text: &str) -> String {
+    return text.replace('|', "\\|");
+}
+
+fn render_problem_header(submission: &render::Submission, full_title: bool) -> String {
+    let label = escape(&problem_label(
+        &submission.title,
+        &submission.problem,
+        full_title,
+    ));
+    if submission.problem.url.is_empty() {
+        return label;
+    }
+    return format!("[{}]({})", label, submission.problem.url);
+}
+
+// display name, with the avatar image in front when there is one
+fn render_user(username: &str, options: &render::RenderOptions) -> String {
+    let name = escape(&options.display(username));
+    match options.aliases.get(username) {
+        Some(alias) if !alias.avatar.is_empty() => {
+            return format!("<img src=\"{}\" width=\"20\"> {}", alias.avatar, name)
+        }
+        _ => return name,
+    }
+}
+
+// markdown can't carry ANSI colors, so the color theme is plain text here
+fn cell_theme(options: &render::RenderOptions) -> render::Theme {
+    match options.theme {
+        render::Theme::Color => return render::Theme::Ascii,
+        theme => return theme,
+    }
+}
+
+fn render_glyph(glyph: Glyph, options: &render::RenderOptions) -> String {
+    return cell_theme(options).glyph(glyph).trim_end().to_string();
+}
+
+fn render_medal(local_rank: u32, options: &render::RenderOptions) -> String {
+    return cell_theme(options).medal(local_rank).trim_end().to_string();
+}
+
+fn render_submission_cell(
+    submission: &render::Submission,
+    options: &render::RenderOptions,
+) -> String {
+    match submission.status {
+        render::SubmissionStatus::Accepted => {
+            let mut cell = format!(
+                "{} {}",
+                render_glyph(Glyph::Accepted, options),
+                finish_time::seconds_to_finish_time(submission.finish_time)
+            );
+            if submission.fail_count > 0 {
+                cell += &format!(
+                    " {}{}",
+                    cell_theme(options).glyph(Glyph::Bug),
+                    submission.fail_count
+                );
+            }
+            if !submission.language.is_empty() {
+                cell += &format!(" `{}`", submission.language);
+            }
+            return cell;
+        }
+        render::SubmissionStatus::Unaccepted => return render_glyph(Glyph::Unaccepted, options),
+        render::SubmissionStatus::Pending => return render_glyph(Glyph::Pending, options),
+    }
+}
+
+fn render_contest_percentile(result: &Option<render::ContestPercentile>) -> String {
+    match result {
+        Some(result) => format!("{} ({:.1}%)", escape(&result.name), result.percentile),
+        None => String::from("-"),
+    }
+}
+
+fn render_row(cells: &[String]) {
+    println!("| {} |", cells.join(" | "));
+}
+
+fn render_table_header(headers: Vec<String>) {
+    let separator = vec![String::from("---"); headers.len()];
+    render_row(&headers);
+    render_row(&separator);
+}
+
+fn render_contest(contest: &render::Contest, options: &render::RenderOptions) {
+    println!("## {}", escape(&contest.name));
+    println!();
+    println!("{}", options.date.format(contest.date));
+    println!();
+
+    // every player of a contest shares the same problem list
+    let problems = contest
+        .players
+        .iter()
+        .map(|player| &player.submissions)
+        .find(|submissions| !submissions.is_empty());
+
+    let mut headers = vec![
+        String::from("Rank"),
+        String::from("User"),
+        String::from("Country"),
+        String::from("Score"),
+        String::from("Finish Time"),
+        String::from("Global Rank"),
+    ];
+    if !options.hide_submission {
+        if let Some(problems) = problems {
+            for submission in problems.iter() {
+                headers.push(render_problem_header(submission, options.full_title));
+            }
+        }
+    }
+    let column_count = headers.len();
+    render_table_header(headers);
+
+    for player in contest.players.iter() {
+        let mut cells = vec![];
+        if !player.attended {
+            cells.push(render_glyph(Glyph::Absent, options));
+            cells.push(render_user(&player.username, options));
+            cells.push(String::from("did not participate"));
+            cells.resize(column_count, String::from(""));
+            render_row(&cells);
+            continue;
+        }
+
+        if player.is_friend {
+            cells.push(render_medal(player.local_rank, options));
+        } else {
+            cells.push(render_glyph(Glyph::Guest, options));
+        }
+        cells.push(render_user(&player.username, options));
+        cells.push(escape(&player.country));
+        cells.push(player.score.to_string());
+        cells.push(finish_time::seconds_to_finish_time(player.finish_time));
+        cells.push(player.global_rank.to_string());
+        if !options.hide_submission {
+            for submission in player.submissions.iter() {
+                cells.push(render_submission_cell(submission, options));
+            }
+        }
+        cells.resize(column_count, String::from(""));
+        render_row(&cells);
+    }
+    println!();
+
+    if !options.hide_submission {
+        render_question_stats(&analytics::question_stats(contest), options);
+    }
+}
+
+fn render_question_stats(stats: &[render::QuestionStats], options: &render::RenderOptions) {
+    if stats.is_empty() {
+        return;
+    }
+
+    render_table_header(vec![
+        String::from("Problem"),
+        String::from("Solved"),
+        String::from("First Solver"),
+        String::from("Fastest"),
+        String::from("Median"),
+        String::from("Avg Wrong"),
+        String::from("Bug Free"),
+    ]);
+    for question in stats.iter() {
+        let label = problem_label(&question.title, &question.problem, options.full_title);
+        let problem = if question.problem.url.is_empty() {
+            escape(&label)
+        } else {
+            format!("[{}]({})", escape(&label), question.problem.url)
+        };
+
+        render_row(&[
+            problem,
+            format!("{}/{}", question.solve_count, question.attend_count),
+            question
+                .first_solver
+                .as_deref()
+                .map_or(String::from("-"), |user| render_user(user, options)),
+            render_optional_time(question.fastest_time),
+            render_optional_time(question.median_time),
+            format!("{:.2}", question.avg_fail_count),
+            format!("{:.0}%", question.bug_free_rate * 100.0),
+        ]);
+    }
+    println!();
+}
+
+pub fn render(object: render::RenderObject, options: &render::RenderOptions) {
+    if object.is_live {
+        println!("_Live, updated {}_", options.date.now());
+        println!();
+    }
+
+    for (contest_idx, contest) in object.data.iter().enumerate() {
+        render_contest(contest, options);
+        if let Some(teams) = &object.teams {
+            render_team_results(&teams.contests[contest_idx], options);
+        }
+    }
+
+    if !object.is_live && object.data.len() >= 2 && !object.aggregate.is_empty() {
+        println!("## Overall Data");
+        println!();
+        render_table_header(vec![
+            String::from("Rank"),
+            String::from("User"),
+            String::from("Total Score"),
+            String::from("Wins"),
+            String::from("Attended"),
+            String::from("Total Time"),
+            String::from("AK Streak"),
+            String::from("Win Streak"),
+            String::from("Bug Free Streak"),
+            String::from("Avg Top"),
+            String::from("Trend"),
+            String::from("Best"),
+            String::from("Worst"),
+        ]);
+
+        for idx in 0..object.aggregate.len() {
+            let aggregate = &object.aggregate[idx];
+            render_row(&[
+                render_medal(1u32 + (idx as u32), options),
+                render_user(&aggregate.username, options),
+                aggregate.total_score.to_string(),
+                aggregate.win_count.to_string(),
+                aggregate.attend_count.to_string(),
+                finish_time::seconds_to_finish_time(aggregate.total_time),
+                aggregate.ak_streak.to_string(),
+                aggregate.win_streak.to_string(),
+                aggregate.bug_free_streak.to_string(),
+                render_percentile(aggregate.avg_percentile),
+                render_trend(aggregate.trend),
+                render_contest_percentile(&aggregate.best_contest),
+                render_contest_percentile(&aggregate.worst_contest),
+            ]);
+        }
+        println!();
+    }
+
+    if !object.is_live && object.data.len() >= 2 {
+        if let Some(teams) = &object.teams {
+            render_team_standings(&teams.overall, options);
+        }
+    }
+
+    if !object.is_live {
+        for season in object.seasons.iter() {
+            render_season(season, options);
+        }
+        render_countries(&object.countries, options);
+    }
+}
+
+fn render_countries(countries: &[render::CountryAggregate], options: &render::RenderOptions) {
+    if countries.is_empty() {
+        return;
+    }
+
+    println!("## Countries");
+    println!();
+    render_table_header(vec![
+        String::from("Country"),
+        String::from("Users"),
+        String::from("Attended"),
+        String::from("Best"),
+        String::from("Best Score"),
+    ]);
+
+    for country in countries.iter() {
+        render_row(&[
+            escape(&country.country),
+            country.user_count.to_string(),
+            country.attend_count.to_string(),
+            render_user(&country.best_username, options),
+            country.best_total_score.to_string(),
+        ]);
+    }
+    println!();
+}
+
+fn render_team_results(results: &[render::TeamResult], options: &render::RenderOptions) {
+    if results.is_empty() {
+        return;
+    }
+
+    render_table_header(vec![
+        String::from("Team Rank"),
+        String::from("Team"),
+        String::from("Score"),
+        String::from("Attended"),
+        String::from("Best Member"),
+        String::from("Best Rank"),
+    ]);
+    for (idx, result) in results.iter().enumerate() {
+        render_row(&[
+            render_medal(1u32 + (idx as u32), options),
+            escape(&result.name),
+            result.score.to_string(),
+            result.attend_count.to_string(),
+            result
+                .best_member
+                .as_deref()
+                .map_or(String::from("-"), |member| render_user(member, options)),
+            result
+                .best_rank
+                .map_or(String::from("-"), |rank| rank.to_string()),
+        ]);
+    }
+    println!();
+}
+
+fn render_team_standings(results: &[render::TeamResult], options: &render::RenderOptions) {
+    println!("## Team Standings");
+    println!();
+    render_table_header(vec![
+        String::from("Rank"),
+        String::from("Team"),
+        String::from("Total Score"),
+        String::from("Wins"),
+        String::from("Attended"),
+        String::from("Best Member"),
+    ]);
+    for (idx, result) in results.iter().enumerate() {
+        render_row(&[
+            render_medal(1u32 + (idx as u32), options),
+            escape(&result.name),
+            result.score.to_string(),
+            result.win_count.to_string(),
+            result.attend_count.to_string(),
+            result
+                .best_member
+                .as_deref()
+                .map_or(String::from("-"), |member| render_user(member, options)),
+        ]);
+    }
+    println!();
+}
+
+fn render_season(season: &render::Season, options: &render::RenderOptions) {
+    if season.contest_count == 0 {
+        return;
+    }
+
+    println!("## Season {}", escape(&render_season_title(season)));
+    println!();
+    render_table_header(vec![
+        String::from("Rank"),
+        String::from("User"),
+        String::from("Points"),
+        String::from("Wins"),
+        String::from("Attended"),
+        String::from("Dropped"),
+    ]);
+
+    for (idx, standing) in season.standings.iter().enumerate() {
+        render_row(&[
+            render_medal(1u32 + (idx as u32), options),
+            render_user(&standing.username, options),
+            standing.points.to_string(),
+            standing.win_count.to_string(),
+            standing.attend_count.to_string(),
+            standing.dropped.to_string(),
+        ]);
+    }
+    println!();
+}
@@ -4,3 +4,19 @@ pub mod converter;
 pub mod cache;
 pub mod live;
 pub mod handler;
+pub mod secrets;
+pub mod markdown;
+pub mod analytics;
+pub mod compare;
+pub mod season;
+pub mod stats;
+pub mod chart;
+pub mod svg;
+pub mod theme;
+pub mod view;
+pub mod country;
+pub mod group;
+pub mod alias;
+pub mod profile;
+pub mod validate;
+pub mod overrides;
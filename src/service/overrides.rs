@@ -0,0 +1,159 @@
+use crate::model::config::Config;
+use serde_json::Value;
+use std::{env, error::Error};
+
+// CONTEST_RANK__LEETCODE__MAX_RANK sets leetcode.max_rank
+const ENV_PREFIX: &str = "CONTEST_RANK__";
+
+pub struct Override {
+    // dotted path like leetcode.max_rank
+    pub key: String,
+    pub value: String,
+    // the env variable name or --set
+    pub source: String,
+}
+
+fn parse_set(set: &str) -> Result<Override, Box<dyn Error>> {
+    let mut parts = set.splitn(2, '=');
+    let key = parts.next().unwrap_or("").trim();
+    let value = match parts.next() {
+        Some(value) => value,
+        None => return Err(format!("expected KEY=VALUE, got {}", set).into()),
+    };
+    if key.is_empty() {
+        return Err(format!("empty key in {}", set).into());
+    }
+
+    return Ok(Override {
+        key: key.to_string(),
+        value: value.to_string(),
+        source: String::from("--set"),
+    });
+}
+
+// env variables first, so --set wins over them
+pub fn collect(sets: Vec<&str>) -> Result<Vec<Override>, Box<dyn Error>> {
+    let mut overrides = Vec::<Override>::new();
+
+    let mut vars: Vec<(String, String)> = env::vars()
+        .filter(|(name, _)| name.starts_with(ENV_PREFIX))
+        .collect();
+    vars.sort();
+    for (name, value) in vars.into_iter() {
+        let key = name[ENV_PREFIX.len()..]
+            .split("__")
+            .map(|part| part.to_lowercase())
+            .collect::<Vec<String>>()
+            .join(".");
+        overrides.push(Override {
+            key,
+            value,
+            source: name,
+        });
+    }
+
+    for set in sets.into_iter() {
+        overrides.push(parse_set(set)?);
+    }
+    return Ok(overrides);
+}
+
+fn lookup<'a>(value: &'a mut Value, key: &str) -> Option<&'a mut Value> {
+    let mut current = value;
+    for part in key.split('.') {
+        current = current.as_object_mut()?.get_mut(part)?;
+    }
+    return Some(current);
+}
+
+// the current value decides how the text is read, so users=alice,bob is a
+// list and theme=ascii doesn't need json quotes
+fn parse_value(current: &Value, text: &str) -> Value {
+    let parsed = serde_json::from_str::<Value>(text).ok();
+    match (current, parsed) {
+        (Value::String(_), _) => return Value::String(text.to_string()),
+        (Value::Array(_), Some(Value::Array(items))) => return Value::Array(items),
+        (Value::Array(_), _) => {
+            return Value::Array(
+                text.split(',')
+                    .map(|item| item.trim())
+                    .filter(|item| !item.is_empty())
+                    .map(|item| {
+                        serde_json::from_str::<Value>(item)
+                            .unwrap_or_else(|_| Value::String(item.to_string()))
+                    })
+                    .collect(),
+            )
+        }
+        (_, Some(parsed)) => return parsed,
+        (_, None) => return Value::String(text.to_string()),
+    }
+}
+
+pub fn apply(config: &Config, overrides: &[Override]) -> Result<Config, Box<dyn Error>> {
+    let mut value = serde_json::to_value(config)?;
+    for item in overrides.iter() {
+        let current = match lookup(&mut value, &item.key) {
+            Some(current) => current,
+            None => {
+                return Err(
+                    format!("{}: unknown config key, from {}", item.key, item.source).into(),
+                )
+            }
+        };
+        *current = parse_value(current, &item.value);
+    }
+
+    match serde_path_to_error::deserialize(value) {
+        Ok(config) => return Ok(config),
+        Err(err) => {
+            let key = err.path().to_string();
+            let source = overrides
+                .iter()
+                .rev()
+                .find(|item| key.starts_with(&item.key))
+                .map(|item| item.source.as_str())
+                .unwrap_or("overrides");
+            return Err(format!("{}: {}, from {}", key, err.into_inner(), source).into());
+        }
+    }
+}
+
+// the config right before and right after the overrides, kept to take
+// them out again when the config is written
+pub struct Layers {
+    pub file: Config,
+    pub merged: Config,
+    pub overrides: Vec<Override>,
+}
+
+impl Layers {
+    // overridden keys go back to their file values; a key that was edited
+    // by a subcommand too is an error, neither value is right to write
+    pub fn restore(&self, config: &Config) -> Result<Config, Box<dyn Error>> {
+        if self.overrides.is_empty() {
+            return Ok(config.clone());
+        }
+
+        let mut value = serde_json::to_value(config)?;
+        let mut file = serde_json::to_value(&self.file)?;
+        let mut merged = serde_json::to_value(&self.merged)?;
+        for item in self.overrides.iter() {
+            let current = match lookup(&mut value, &item.key) {
+                Some(current) => current,
+                None => continue,
+            };
+            if lookup(&mut merged, &item.key).map(|merged| merged != current) == Some(true) {
+                return Err(format!(
+                    "{} is overridden by {} and can't be edited in the same run",
+                    item.key, item.source
+                )
+                .into());
+            }
+            if let Some(previous) = lookup(&mut file, &item.key) {
+                *current = previous.clone();
+            }
+        }
+        return Ok(serde_json::from_value(value)?);
+    }
+}
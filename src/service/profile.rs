@@ -0,0 +1,115 @@
+use crate::model::config::{Config, ProfileConfig};
+use clap::ArgMatches;
+use std::mem;
+
+pub const DEFAULT_PROFILE: &str = "default";
+
+fn swap_option<T>(value: &mut Option<T>, current: &mut T) {
+    if let Some(value) = value {
+        mem::swap(value, current);
+    }
+}
+
+// exchanges a profile with the top level settings, so everything downstream
+// reads and edits the profile in place; swapping again restores the config
+pub fn swap(config: &mut Config, name: &str) -> bool {
+    if name.is_empty() || name == DEFAULT_PROFILE {
+        return true;
+    }
+
+    let mut profile = match config.profiles.remove(name) {
+        Some(profile) => profile,
+        None => return false,
+    };
+
+    mem::swap(&mut profile.users, &mut config.leetcode.users);
+    mem::swap(&mut profile.contests, &mut config.leetcode.contests);
+    mem::swap(&mut profile.live_users, &mut config.leetcode.live_users);
+    mem::swap(
+        &mut profile.live_contests,
+        &mut config.leetcode.live_contests,
+    );
+    swap_option(&mut profile.theme, &mut config.theme);
+    swap_option(&mut profile.date, &mut config.date);
+    swap_option(&mut profile.views, &mut config.views);
+    swap_option(&mut profile.groups, &mut config.groups);
+    swap_option(&mut profile.seasons, &mut config.seasons);
+
+    config.profiles.insert(name.to_string(), profile);
+    return true;
+}
+
+pub fn subcommand_match(profile_matches: &ArgMatches, config: &mut Config) -> bool {
+    match profile_matches.subcommand() {
+        ("add", Some(arg_matches)) => {
+            let name = arg_matches.value_of("name").unwrap();
+            if name == DEFAULT_PROFILE || config.profiles.contains_key(name) {
+                println!("[INFO] ❌ Profile {} already exists", name);
+                return false;
+            }
+
+            let mut profile = ProfileConfig::default();
+            if arg_matches.is_present("copy") {
+                profile.users = config.leetcode.users.clone();
+                profile.contests = config.leetcode.contests.clone();
+                profile.live_users = config.leetcode.live_users.clone();
+                profile.live_contests = config.leetcode.live_contests.clone();
+            }
+            config.profiles.insert(name.to_string(), profile);
+            println!("[INFO] 🔧 Added profile {}", name);
+            return true;
+        }
+        ("use", Some(arg_matches)) => {
+            let name = arg_matches.value_of("name").unwrap();
+            if name == DEFAULT_PROFILE {
+                config.current_profile = String::from("");
+            } else if config.profiles.contains_key(name) {
+                config.current_profile = name.to_string();
+            } else {
+                println!("[INFO] ❌ Profile {} doesn't exist", name);
+                return false;
+            }
+            println!("[INFO] 🔧 Switched to profile {}", name);
+            return true;
+        }
+        ("delete", Some(arg_matches)) => {
+            let name = arg_matches.value_of("name").unwrap();
+            if config.profiles.remove(name).is_none() {
+                println!("[INFO] ❌ Profile {} doesn't exist", name);
+                return false;
+            }
+            if config.current_profile == name {
+                config.current_profile = String::from("");
+            }
+            println!("[INFO] 🔧 Removed profile {}", name);
+            return true;
+        }
+        ("list", _) => {
+            let current = if config.current_profile.is_empty() {
+                DEFAULT_PROFILE
+            } else {
+                &config.current_profile
+            };
+            let print_profile = |name: &str, users: usize, contests: usize| {
+                let marker = if name == current { "*" } else { " " };
+                println!(
+                    "{} {:<24} users {:<4} contests {}",
+                    marker, name, users, contests
+                );
+            };
+
+            print_profile(
+                DEFAULT_PROFILE,
+                config.leetcode.users.len(),
+                config.leetcode.contests.len(),
+            );
+            for (name, profile) in config.profiles.iter() {
+                print_profile(name, profile.users.len(), profile.contests.len());
+            }
+            return false;
+        }
+        _ => {}
+    }
+
+    return false;
+}
@@ -1,23 +1,26 @@
-use crate::{model::render, utils::finish_time};
-use chrono::{prelude, TimeZone};
+use crate::{
+    model::render,
+    service::{
+        analytics, markdown,
+        theme::{pad, width, Glyph},
+        view,
+    },
+    utils::finish_time,
+};
 
-fn render_medal(local_rank: u32) -> &'static str {
-    match local_rank {
-        1 => "🏅️",
-        2 => "🥈",
-        3 => "🥉",
-        _ => "👴",
+fn render_fail_count(fail_count: u32, theme: render::Theme) -> String {
+    match fail_count {
+        0 => theme.glyph(Glyph::BugFree),
+        _ => format!("{}{}", theme.glyph(Glyph::Bug), fail_count),
     }
 }
 
-fn render_fail_count(fail_count: u32) -> String {
-    match fail_count {
-        0 => String::from("🌟BUG FREE"),
-        _ => format!("🐛{}", fail_count),
+fn render_ak(player: &render::User, theme: render::Theme) -> String {
+    // history entries have no submission detail
+    if player.submissions.is_empty() {
+        return String::from("");
     }
-}
 
-fn render_ak(player: &render::User) -> &'static str {
     let mut is_ak = true;
     let mut is_all_bug_free = true;
 
@@ -35,85 +38,634 @@ fn render_ak(player: &render::User) -> &'static str {
     }
 
     if is_ak && is_all_bug_free {
-        return "🐂AK";
+        return theme.glyph(Glyph::AkBugFree);
     } else if is_ak {
-        return "🎉AK";
+        return theme.glyph(Glyph::Ak);
     } else {
-        return "";
+        return String::from("");
+    }
+}
+
+fn render_attempts(submission: &render::Submission, theme: render::Theme) {
+    for attempt in submission.attempts.iter() {
+        let icon = if attempt.verdict == "Accepted" {
+            theme.glyph(Glyph::Accepted)
+        } else {
+            theme.glyph(Glyph::Unaccepted)
+        };
+        println!(
+            "      {}{:<12} {}{:<24} {}",
+            theme.glyph(Glyph::AttemptTime),
+            finish_time::seconds_to_finish_time(attempt.time),
+            icon,
+            attempt.verdict,
+            attempt.language
+        );
+    }
+}
+
+fn render_column(player: &render::User, column: render::Column, theme: render::Theme) -> String {
+    match column {
+        render::Column::Country => {
+            let country = if player.country.is_empty() {
+                "-"
+            } else {
+                &player.country
+            };
+            return format!("{}{}", theme.glyph(Glyph::Country), pad(country, 16));
+        }
+        render::Column::FinishTime => {
+            return format!(
+                "{}{:<12}",
+                theme.glyph(Glyph::FinishTime),
+                finish_time::seconds_to_finish_time(player.finish_time)
+            )
+        }
+        render::Column::GlobalRank => {
+            return format!(
+                "{}{:<4}",
+                theme.glyph(Glyph::GlobalRank),
+                player.global_rank
+            )
+        }
+        render::Column::Score => {
+            return format!("{}{:<6}", theme.glyph(Glyph::Score), player.score)
+        }
+        render::Column::Ak => return render_ak(player, theme),
+    }
+}
+
+pub fn render_optional_time(time: Option<i64>) -> String {
+    match time {
+        Some(time) => finish_time::seconds_to_finish_time(time),
+        None => String::from("-"),
+    }
+}
+
+fn render_question_stats(
+    stats: &[render::QuestionStats],
+    options: &render::RenderOptions,
+    title_width: usize,
+) {
+    if stats.is_empty() {
+        return;
+    }
+
+    let theme = options.theme;
+    println!("  {}", theme.glyph(Glyph::Problems));
+    for question in stats.iter() {
+        let label = problem_label(&question.title, &question.problem, options.full_title);
+        let solved = format!("{}/{}", question.solve_count, question.attend_count);
+
+        println!(
+            "    {} {}{:<6} {}{} {}{:<12} {}{:<12} {}{:<6.2} {}{:.0}%",
+            pad(&label, title_width + 1),
+            theme.glyph(Glyph::Solved),
+            solved,
+            theme.glyph(Glyph::FirstSolver),
+            pad(
+                &question
+                    .first_solver
+                    .as_deref()
+                    .map_or(String::from("-"), |user| options.display(user)),
+                24
+            ),
+            theme.glyph(Glyph::Fastest),
+            render_optional_time(question.fastest_time),
+            theme.glyph(Glyph::Median),
+            render_optional_time(question.median_time),
+            theme.glyph(Glyph::Bug),
+            question.avg_fail_count,
+            theme.glyph(Glyph::BugFreeRate),
+            question.bug_free_rate * 100.0
+        );
     }
 }
 
-pub fn render_date(date: chrono::DateTime<chrono::Local>) -> String {
-    return date
-        .format_localized("%Y-%m-%d %a %H:%M:%S", prelude::Locale::ja_JP)
-        .to_string();
+pub fn problem_label(title: &str, problem: &render::Problem, full_title: bool) -> String {
+    if !full_title || problem.title.is_empty() {
+        return title.to_string();
+    }
+
+    if problem.difficulty.is_empty() {
+        return format!("{} {} ({}pt)", title, problem.title, problem.credit);
+    }
+    return format!(
+        "{} {} ({}, {}pt)",
+        title, problem.title, problem.difficulty, problem.credit
+    );
+}
+
+pub fn output(object: render::RenderObject, options: &render::RenderOptions) {
+    match options.format {
+        render::OutputFormat::Text => render(object, options),
+        render::OutputFormat::Markdown => markdown::render(object, options),
+        render::OutputFormat::Json => {
+            let mut object = object;
+            attach_aliases(&mut object, options);
+            println!("{}", serde_json::to_string_pretty(&object).unwrap())
+        }
+    }
+}
+
+// text and markdown print aliases in place of usernames, json keeps the
+// username and carries the alias next to it
+fn attach_aliases(object: &mut render::RenderObject, options: &render::RenderOptions) {
+    for contest in object.data.iter_mut() {
+        for player in contest.players.iter_mut() {
+            player.alias = options.aliases.get(&player.username).cloned();
+        }
+    }
+    for aggregate in object.aggregate.iter_mut() {
+        aggregate.alias = options.aliases.get(&aggregate.username).cloned();
+    }
 }
 
-pub fn render(object: render::RenderObject, hide_submission: bool) {
+pub fn render(object: render::RenderObject, options: &render::RenderOptions) {
+    let theme = options.theme;
+
     // render for each contest
     if object.is_live {
-        println!("🎦[Live] Updated {}", render_date(prelude::Local::now()));
+        println!(
+            "{}[Live] Updated {}",
+            theme.glyph(Glyph::Live),
+            options.date.now()
+        );
     }
 
-    for contest in object.data.iter() {
+    for (contest_idx, contest) in object.data.iter().enumerate() {
         println!(
-            "🏆{:<42}{}",
-            contest.name,
-            render_date(prelude::Local.timestamp(contest.date, 0))
+            "{}{}{}",
+            theme.glyph(Glyph::Contest),
+            pad(&contest.name, 42),
+            options.date.format(contest.date)
         );
 
-        for player in contest.players.iter() {
-            println!(
-                "  {}{:<24} 🍺{:<12} 📊{:<4} ✨{:<6} {}",
-                render_medal(player.local_rank),
-                player.username,
-                finish_time::seconds_to_finish_time(player.finish_time),
-                player.global_rank,
-                player.score,
-                render_ak(player)
-            );
+        let title_width = contest
+            .players
+            .iter()
+            .flat_map(|player| player.submissions.iter())
+            .map(|submission| {
+                width(&problem_label(
+                    &submission.title,
+                    &submission.problem,
+                    options.full_title,
+                ))
+            })
+            .max()
+            .unwrap_or(0)
+            .max(6);
 
-            if !hide_submission {
+        for player in view::select_players(&contest.players, &options.view) {
+            if !player.attended {
+                println!(
+                    "  {}{} did not participate",
+                    theme.glyph(Glyph::Absent),
+                    pad(&options.display(&player.username), 24)
+                );
+                continue;
+            }
+
+            let medal = if player.is_friend {
+                theme.medal(player.local_rank)
+            } else {
+                theme.glyph(Glyph::Guest)
+            };
+            let mut line = format!("  {}{}", medal, pad(&options.display(&player.username), 24));
+            for column in options.view.columns.iter() {
+                line += " ";
+                line += &render_column(player, *column, theme);
+            }
+            println!("{}", line);
+
+            if !options.hide_submission {
                 for tid in 0..player.submissions.len() {
                     let submission = &player.submissions[tid];
+                    let title =
+                        problem_label(&submission.title, &submission.problem, options.full_title);
 
                     match submission.status {
                         render::SubmissionStatus::Accepted => {
                             println!(
-                                "    ✅{:<6} ✨{:<13} ⏰{:<12} {}",
-                                submission.title,
+                                "    {}{} {}{:<13} {}{:<12} {} {}",
+                                theme.glyph(Glyph::Accepted),
+                                pad(&title, title_width),
+                                theme.glyph(Glyph::Score),
                                 submission.score,
+                                theme.glyph(Glyph::SubmitTime),
                                 finish_time::seconds_to_finish_time(submission.finish_time),
-                                render_fail_count(submission.fail_count)
+                                pad(&render_fail_count(submission.fail_count, theme), 12),
+                                submission.language
                             );
                         }
                         render::SubmissionStatus::Unaccepted => {
-                            println!("    ❌{:<6} ✨{:<14}", submission.title, submission.score);
+                            println!(
+                                "    {}{} {}{:<14}",
+                                theme.glyph(Glyph::Unaccepted),
+                                pad(&title, title_width),
+                                theme.glyph(Glyph::Score),
+                                submission.score
+                            );
                         }
                         render::SubmissionStatus::Pending => {
-                            println!("    ⏳{:<6} ✨{:<14}", submission.title, submission.score);
+                            println!(
+                                "    {}{} {}{:<14}",
+                                theme.glyph(Glyph::Pending),
+                                pad(&title, title_width),
+                                theme.glyph(Glyph::Score),
+                                submission.score
+                            );
                         }
                     }
+
+                    if options.show_attempts {
+                        render_attempts(submission, theme);
+                    }
                 }
             }
         }
+
+        if !options.hide_submission {
+            render_question_stats(&analytics::question_stats(contest), options, title_width);
+        }
+        if let Some(teams) = &object.teams {
+            render_team_results(&teams.contests[contest_idx], options);
+        }
         println!("");
     }
 
     if !object.is_live && object.data.len() >= 2 && !object.aggregate.is_empty() {
-        println!("🍎Overall Data");
+        println!("{}", theme.glyph(Glyph::Overall));
         // render aggregate data
         for idx in 0..object.aggregate.len() {
             let aggregate = &object.aggregate[idx];
+            if !view::is_visible(&options.view, &aggregate.username, &aggregate.country) {
+                continue;
+            }
+
             println!(
-                "  {}{:<24} ✨{:<6} 🏅️{:<3} ⚡️{:<4} ⏰{}",
-                render_medal(1u32 + (idx as u32)),
-                aggregate.username,
+                "  {}{} {}{:<6} {}{:<3} {}{:<4} {}{}",
+                theme.medal(1u32 + (idx as u32)),
+                pad(&options.display(&aggregate.username), 24),
+                theme.glyph(Glyph::Score),
                 aggregate.total_score,
+                theme.glyph(Glyph::Wins),
                 aggregate.win_count,
+                theme.glyph(Glyph::Attended),
                 aggregate.attend_count,
+                theme.glyph(Glyph::TotalTime),
                 finish_time::seconds_to_finish_time(aggregate.total_time)
             );
+            render_aggregate_stats(aggregate, theme);
+        }
+    }
+
+    if !object.is_live && object.data.len() >= 2 {
+        if let Some(teams) = &object.teams {
+            render_team_standings(&teams.overall, options);
+        }
+    }
+
+    if !object.is_live {
+        for season in object.seasons.iter() {
+            render_season(season, options);
+        }
+        render_countries(&object.countries, options);
+    }
+}
+
+fn render_countries(countries: &[render::CountryAggregate], options: &render::RenderOptions) {
+    if countries.is_empty() {
+        return;
+    }
+
+    let theme = options.theme;
+    println!();
+    println!("{}", theme.glyph(Glyph::Countries));
+    for country in countries.iter() {
+        println!(
+            "  {}{} {}{:<4} {}{:<4} {}{} {}{}",
+            theme.glyph(Glyph::Country),
+            pad(&country.country, 24),
+            theme.glyph(Glyph::Members),
+            country.user_count,
+            theme.glyph(Glyph::Attended),
+            country.attend_count,
+            theme.glyph(Glyph::Best),
+            options.display(&country.best_username),
+            theme.glyph(Glyph::Score),
+            country.best_total_score
+        );
+    }
+}
+
+fn render_best_member(result: &render::TeamResult, options: &render::RenderOptions) -> String {
+    match (&result.best_member, result.best_rank) {
+        (Some(member), Some(rank)) => format!("{} #{}", options.display(member), rank),
+        (Some(member), None) => options.display(member),
+        _ => String::from("-"),
+    }
+}
+
+fn render_team_results(results: &[render::TeamResult], options: &render::RenderOptions) {
+    if results.is_empty() {
+        return;
+    }
+
+    let theme = options.theme;
+    println!("  {}", theme.glyph(Glyph::Teams));
+    for (idx, result) in results.iter().enumerate() {
+        println!(
+            "    {}{} {}{:<6} {}{:<4} {}{}",
+            theme.medal(1u32 + (idx as u32)),
+            pad(&result.name, 24),
+            theme.glyph(Glyph::Score),
+            result.score,
+            theme.glyph(Glyph::Attended),
+            result.attend_count,
+            theme.glyph(Glyph::Best),
+            render_best_member(result, options)
+        );
+    }
+}
+
+fn render_team_standings(results: &[render::TeamResult], options: &render::RenderOptions) {
+    let theme = options.theme;
+    println!();
+    println!("{}", theme.glyph(Glyph::TeamStandings));
+    for (idx, result) in results.iter().enumerate() {
+        println!(
+            "  {}{} {}{:<6} {}{:<3} {}{:<4} {}{}",
+            theme.medal(1u32 + (idx as u32)),
+            pad(&result.name, 24),
+            theme.glyph(Glyph::Score),
+            result.score,
+            theme.glyph(Glyph::Wins),
+            result.win_count,
+            theme.glyph(Glyph::Attended),
+            result.attend_count,
+            theme.glyph(Glyph::Best),
+            result
+                .best_member
+                .as_deref()
+                .map_or(String::from("-"), |member| options.display(member))
+        );
+    }
+}
+
+pub fn render_percentile(percentile: Option<f64>) -> String {
+    match percentile {
+        Some(percentile) => format!("{:.1}%", percentile),
+        None => String::from("-"),
+    }
+}
+
+pub fn render_trend(trend: Option<f64>) -> String {
+    match trend {
+        Some(trend) => format!("{:+.1}", trend),
+        None => String::from("-"),
+    }
+}
+
+fn render_aggregate_stats(aggregate: &render::UserAggregate, theme: render::Theme) {
+    println!(
+        "    {}{:<3} {}{:<3} {}{:<3} {}{:<8} {}{}",
+        theme.glyph(Glyph::AkStreak),
+        aggregate.ak_streak,
+        theme.glyph(Glyph::WinStreak),
+        aggregate.win_streak,
+        theme.glyph(Glyph::BugFreeStreak),
+        aggregate.bug_free_streak,
+        theme.glyph(Glyph::AvgTop),
+        render_percentile(aggregate.avg_percentile),
+        theme.glyph(Glyph::Trend),
+        render_trend(aggregate.trend)
+    );
+
+    if let (Some(best), Some(worst)) = (&aggregate.best_contest, &aggregate.worst_contest) {
+        println!(
+            "    {}{} top {:.1}%  {}{} top {:.1}%",
+            theme.glyph(Glyph::Best),
+            best.name,
+            best.percentile,
+            theme.glyph(Glyph::Worst),
+            worst.name,
+            worst.percentile
+        );
+    }
+}
+
+pub fn render_season_title(season: &render::Season) -> String {
+    let mut title = format!("{} ({} contests", season.name, season.contest_count);
+    if season.best_of > 0 {
+        title += &format!(", best {}", season.best_of);
+    }
+    return title + ")";
+}
+
+fn render_season(season: &render::Season, options: &render::RenderOptions) {
+    if season.contest_count == 0 {
+        return;
+    }
+
+    let theme = options.theme;
+    println!();
+    println!(
+        "{}{}",
+        theme.glyph(Glyph::Season),
+        render_season_title(season)
+    );
+    for (idx, standing) in season.standings.iter().enumerate() {
+        println!(
+            "  {}{} {}{:<6} {}{:<3} {}{:<4} {}{}",
+            theme.medal(1u32 + (idx as u32)),
+            pad(&options.display(&standing.username), 24),
+            theme.glyph(Glyph::Points),
+            standing.points,
+            theme.glyph(Glyph::Wins),
+            standing.win_count,
+            theme.glyph(Glyph::Attended),
+            standing.attend_count,
+            theme.glyph(Glyph::Dropped),
+            standing.dropped
+        );
+    }
+}
+
+fn render_rating(rating: Option<f64>, previous: Option<f64>) -> String {
+    match (rating, previous) {
+        (Some(rating), Some(previous)) => format!("{:.0}({:+.0})", rating, rating - previous),
+        (Some(rating), None) => format!("{:.0}", rating),
+        _ => String::from("-"),
+    }
+}
+
+pub fn render_timeline(object: render::RenderObject, options: &render::RenderOptions) {
+    let theme = options.theme;
+
+    for aggregate in object.aggregate.iter() {
+        println!(
+            "{}{} {}{:<4} {}{}",
+            theme.glyph(Glyph::User),
+            pad(&options.display(&aggregate.username), 24),
+            theme.glyph(Glyph::Attended),
+            aggregate.attend_count,
+            theme.glyph(Glyph::Wins),
+            aggregate.win_count
+        );
+
+        let mut previous_rating = None;
+        for contest in object.data.iter() {
+            let player = contest
+                .players
+                .iter()
+                .find(|player| player.username == aggregate.username);
+
+            if let Some(player) = player {
+                let solved = format!("{}/{}", player.problems_solved, player.total_problems);
+                println!(
+                    "  {} {} {}{:<6} {}{:<12} {}{:<5} {}{}",
+                    options.date.format(contest.date),
+                    pad(&contest.name, 36),
+                    theme.glyph(Glyph::GlobalRank),
+                    player.global_rank,
+                    theme.glyph(Glyph::Rating),
+                    render_rating(player.rating, previous_rating),
+                    theme.glyph(Glyph::Solved),
+                    solved,
+                    theme.glyph(Glyph::TotalTime),
+                    finish_time::seconds_to_finish_time(player.finish_time)
+                );
+                previous_rating = player.rating;
+            }
+        }
+        println!();
+    }
+}
+
+fn render_time_diff(diff: i64) -> String {
+    let sign = if diff < 0 { "-" } else { "+" };
+    return format!(
+        "{}{}",
+        sign,
+        finish_time::seconds_to_finish_time(diff.abs())
+    );
+}
+
+fn render_duel_rank(rank: Option<u32>, theme: render::Theme) -> String {
+    match rank {
+        Some(rank) => format!("#{}", rank),
+        None => theme.glyph(Glyph::Absent).trim_end().to_string(),
+    }
+}
+
+pub fn render_head_to_head(result: &render::HeadToHead, options: &render::RenderOptions) {
+    let theme = options.theme;
+    let (lhs, rhs) = (
+        options.display(&result.users.0),
+        options.display(&result.users.1),
+    );
+    println!("{}{} vs {}", theme.glyph(Glyph::Versus), lhs, rhs);
+
+    for contest in result.contests.iter() {
+        println!(
+            "{}{}{}",
+            theme.glyph(Glyph::Contest),
+            pad(&contest.name, 42),
+            options.date.format(contest.date)
+        );
+        println!(
+            "  {} {} {} {}",
+            pad(&lhs, 24),
+            pad(&render_duel_rank(contest.rank.0, theme), 6),
+            pad(&rhs, 24),
+            pad(&render_duel_rank(contest.rank.1, theme), 6)
+        );
+
+        let winner = match &contest.winner {
+            Some(winner) => options.display(winner),
+            None => continue,
+        };
+        println!(
+            "  {}{} {}{:<+6} {}{}",
+            theme.medal(1),
+            pad(&winner, 23),
+            theme.glyph(Glyph::Score),
+            contest.score_diff,
+            theme.glyph(Glyph::TotalTime),
+            render_time_diff(contest.time_diff)
+        );
+
+        for question in contest.questions.iter() {
+            match (&question.winner, question.time_diff) {
+                (Some(winner), Some(diff)) => println!(
+                    "    {:<6} {}{} by {}",
+                    question.title,
+                    theme.glyph(Glyph::Fastest),
+                    pad(&options.display(winner), 24),
+                    finish_time::seconds_to_finish_time(diff.abs())
+                ),
+                (Some(winner), None) => println!(
+                    "    {:<6} {}{} only solver",
+                    question.title,
+                    theme.glyph(Glyph::Accepted),
+                    pad(&options.display(winner), 24)
+                ),
+                (None, _) => println!(
+                    "    {:<6} {}nobody solved",
+                    question.title,
+                    theme.glyph(Glyph::Unaccepted)
+                ),
+            }
+        }
+    }
+
+    println!();
+    println!(
+        "{}{} {} : {} {}  (questions {} : {})",
+        theme.glyph(Glyph::Summary),
+        lhs,
+        result.wins.0,
+        result.wins.1,
+        rhs,
+        result.question_wins.0,
+        result.question_wins.1
+    );
+}
+
+pub fn render_matrix(matrix: &render::HeadToHeadMatrix, options: &render::RenderOptions) {
+    let users: Vec<String> = matrix
+        .users
+        .iter()
+        .map(|user| options.display(user))
+        .collect();
+    let column_width = users
+        .iter()
+        .map(|user| width(user))
+        .max()
+        .unwrap_or(0)
+        .max(4)
+        + 2;
+
+    println!(
+        "{}Head-to-head matrix, row finished ahead of column",
+        options.theme.glyph(Glyph::Matrix)
+    );
+    print!("  {}", pad("", column_width));
+    for user in users.iter() {
+        print!("{}", pad(user, column_width));
+    }
+    println!();
+
+    for (i, user) in users.iter().enumerate() {
+        print!("  {}", pad(user, column_width));
+        for j in 0..matrix.users.len() {
+            if i == j {
+                print!("{}", pad("-", column_width));
+            } else {
+                print!("{}", pad(&matrix.wins[i][j].to_string(), column_width));
+            }
         }
+        println!();
     }
 }
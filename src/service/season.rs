@@ -0,0 +1,125 @@
+use crate::{
+    model::{config::SeasonConfig, render},
+    utils::date::DateFormatter,
+};
+use chrono::{Duration, NaiveDate};
+use std::collections::HashMap;
+
+fn parse_date(date: &str, season: &str, formatter: &DateFormatter) -> Option<i64> {
+    if date.is_empty() {
+        return None;
+    }
+
+    match NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+        Ok(date) => return formatter.start_of_day(date),
+        Err(err) => {
+            println!(
+                "[WARN] Invalid date in season, season={}, date={}, err={}",
+                season, date, err
+            );
+            return None;
+        }
+    }
+}
+
+fn in_season(contest: &render::Contest, season: &SeasonConfig, date: &DateFormatter) -> bool {
+    if !season.contests.is_empty() && !season.contests.contains(&contest.id) {
+        return false;
+    }
+
+    if let Some(start) = parse_date(&season.start, &season.name, date) {
+        if contest.date < start {
+            return false;
+        }
+    }
+    // the end date is inclusive, so compare against the next midnight
+    if let Some(end) = parse_date(&season.end, &season.name, date) {
+        if contest.date >= end + Duration::days(1).num_seconds() {
+            return false;
+        }
+    }
+    return true;
+}
+
+pub fn standings(
+    object: &render::RenderObject,
+    season: &SeasonConfig,
+    date: &DateFormatter,
+) -> render::Season {
+    let contests: Vec<&render::Contest> = object
+        .data
+        .iter()
+        .filter(|contest| in_season(contest, season, date))
+        .collect();
+
+    let mut results = HashMap::<String, Vec<u32>>::new();
+    let mut standings = Vec::<render::SeasonStanding>::new();
+    for contest in contests.iter() {
+        for player in contest
+            .players
+            .iter()
+            .filter(|player| player.attended && player.is_friend)
+        {
+            let points = season
+                .points
+                .get((player.local_rank - 1) as usize)
+                .cloned()
+                .unwrap_or(0);
+            results
+                .entry(player.username.clone())
+                .or_insert_with(Vec::new)
+                .push(points);
+
+            let standing = match standings
+                .iter_mut()
+                .position(|standing| standing.username == player.username)
+            {
+                Some(idx) => &mut standings[idx],
+                None => {
+                    standings.push(render::SeasonStanding {
+                        username: player.username.clone(),
+                        points: 0,
+                        dropped: 0,
+                        attend_count: 0,
+                        win_count: 0,
+                    });
+                    standings.last_mut().unwrap()
+                }
+            };
+            standing.attend_count += 1;
+            if player.local_rank == 1 {
+                standing.win_count += 1;
+            }
+        }
+    }
+
+    for standing in standings.iter_mut() {
+        let mut points = results.remove(&standing.username).unwrap_or_default();
+        points.sort_by(|lhs, rhs| rhs.cmp(lhs));
+
+        let counted = if season.best_of > 0 {
+            points.len().min(season.best_of as usize)
+        } else {
+            points.len()
+        };
+        standing.points = points[..counted].iter().sum::<u32>()
+            + standing.attend_count * season.participation_bonus;
+        standing.dropped = points[counted..].iter().sum();
+    }
+
+    // ties go to the user with more wins, then more contests attended
+    standings.sort_by(|lhs, rhs| {
+        return rhs
+            .points
+            .cmp(&lhs.points)
+            .then(rhs.win_count.cmp(&lhs.win_count))
+            .then(rhs.attend_count.cmp(&lhs.attend_count));
+    });
+
+    return render::Season {
+        name: season.name.clone(),
+        contest_count: contests.len(),
+        best_of: season.best_of,
+        standings,
+    };
+}
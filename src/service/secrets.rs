@@ -0,0 +1,112 @@
+use crate::model::config::Credentials;
+use std::{
+    collections::HashMap,
+    env,
+    fs::File,
+    path::{Path, PathBuf},
+};
+
+fn env_key(website: &str, name: &str) -> String {
+    return format!("CONTEST_RANK_{}_{}", website.to_uppercase(), name);
+}
+
+// relative to the config file, so the default ./secrets.json sits next to
+// it wherever the config was found
+fn secrets_path(path: &str, config_dir: &str) -> PathBuf {
+    let path = Path::new(path);
+    if path.is_relative() && !config_dir.is_empty() {
+        return Path::new(config_dir).join(path);
+    }
+    return path.to_path_buf();
+}
+
+fn read_secrets_file(website: &str, path: &Path) -> Option<Credentials> {
+    if !path.exists() {
+        return None;
+    }
+
+    match File::open(path) {
+        Ok(file) => match serde_json::from_reader::<File, HashMap<String, Credentials>>(file) {
+            Ok(mut secrets) => return secrets.remove(website),
+            Err(err) => println!(
+                "[ERROR] When parsing secrets file, ignored, path={}, err={}",
+                path.display(),
+                err
+            ),
+        },
+        Err(err) => println!(
+            "[ERROR] When reading secrets file, ignored, path={}, err={}",
+            path.display(),
+            err
+        ),
+    }
+
+    return None;
+}
+
+// secrets file is keyed by website name, environment variables
+// CONTEST_RANK_<WEBSITE>_COOKIE / _CSRF_TOKEN take precedence over it
+pub fn read_credentials(website: &str, path: &str, config_dir: &str) -> Credentials {
+    let mut credentials = if path.is_empty() {
+        Credentials::default()
+    } else {
+        read_secrets_file(website, &secrets_path(path, config_dir)).unwrap_or_default()
+    };
+
+    if let Ok(cookie) = env::var(env_key(website, "COOKIE")) {
+        credentials.cookie = cookie;
+    }
+    if let Ok(csrf_token) = env::var(env_key(website, "CSRF_TOKEN")) {
+        credentials.csrf_token = csrf_token;
+    }
+
+    return credentials;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn secrets_path_follows_config_dir() {
+        assert_eq!(
+            secrets_path("./secrets.json", "/home/me/.config/contest-rank"),
+            Path::new("/home/me/.config/contest-rank/./secrets.json")
+        );
+        assert_eq!(
+            secrets_path("/etc/secrets.json", "/home/me/.config/contest-rank"),
+            Path::new("/etc/secrets.json")
+        );
+        assert_eq!(
+            secrets_path("./secrets.json", ""),
+            Path::new("./secrets.json")
+        );
+    }
+
+    #[test]
+    fn env_takes_precedence_over_file() {
+        // a website name of its own, other tests never set these variables
+        let dir = env::temp_dir().join("contest-rank-secrets-test");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("secrets.json"),
+            r#"{"secretstest": {"cookie": "file-cookie", "csrf_token": "file-token"}}"#,
+        )
+        .unwrap();
+        let config_dir = dir.to_string_lossy().to_string();
+
+        let credentials = read_credentials("secretstest", "./secrets.json", &config_dir);
+        assert_eq!(credentials.cookie, "file-cookie");
+        assert_eq!(credentials.csrf_token, "file-token");
+
+        env::set_var("CONTEST_RANK_SECRETSTEST_COOKIE", "env-cookie");
+        let credentials = read_credentials("secretstest", "./secrets.json", &config_dir);
+        env::remove_var("CONTEST_RANK_SECRETSTEST_COOKIE");
+        assert_eq!(credentials.cookie, "env-cookie");
+        assert_eq!(credentials.csrf_token, "file-token");
+
+        let credentials = read_credentials("othersite", "./secrets.json", &config_dir);
+        assert!(credentials.cookie.is_empty() && credentials.csrf_token.is_empty());
+    }
+}
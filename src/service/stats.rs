@@ -0,0 +1,130 @@
+use crate::model::render;
+
+fn is_ak(player: &render::User) -> bool {
+    return !player.submissions.is_empty()
+        && player
+            .submissions
+            .iter()
+            .all(|submission| submission.status == render::SubmissionStatus::Accepted);
+}
+
+fn is_bug_free(player: &render::User) -> bool {
+    return player
+        .submissions
+        .iter()
+        .any(|submission| submission.status == render::SubmissionStatus::Accepted)
+        && player
+            .submissions
+            .iter()
+            .all(|submission| submission.fail_count == 0);
+}
+
+fn percentile(contest: &render::Contest, player: &render::User) -> Option<f64> {
+    if contest.participant_count == 0 || player.global_rank == 0 {
+        return None;
+    }
+    return Some(player.global_rank as f64 / contest.participant_count as f64 * 100.0);
+}
+
+// least squares slope, flipped so that climbing the ranking is positive
+fn trend(percentiles: &[f64]) -> Option<f64> {
+    if percentiles.len() < 2 {
+        return None;
+    }
+
+    let n = percentiles.len() as f64;
+    let x_mean = (n - 1.0) / 2.0;
+    let y_mean = percentiles.iter().sum::<f64>() / n;
+    let mut numerator = 0.0;
+    let mut denominator = 0.0;
+    for (x, y) in percentiles.iter().enumerate() {
+        numerator += (x as f64 - x_mean) * (y - y_mean);
+        denominator += (x as f64 - x_mean) * (x as f64 - x_mean);
+    }
+    return Some(-numerator / denominator);
+}
+
+struct Streak {
+    current: u32,
+    longest: u32,
+}
+
+impl Streak {
+    fn new() -> Self {
+        return Streak {
+            current: 0,
+            longest: 0,
+        };
+    }
+
+    fn push(&mut self, extended: bool) {
+        self.current = if extended { self.current + 1 } else { 0 };
+        self.longest = self.longest.max(self.current);
+    }
+}
+
+pub fn fill_stats(object: &mut render::RenderObject, trend_window: usize) {
+    let mut contests: Vec<&render::Contest> = object.data.iter().collect();
+    contests.sort_by(|lhs, rhs| lhs.date.cmp(&rhs.date));
+
+    for aggregate in object.aggregate.iter_mut() {
+        let mut ak = Streak::new();
+        let mut win = Streak::new();
+        let mut bug_free = Streak::new();
+        let mut results = Vec::<(&str, f64)>::new();
+
+        for contest in contests.iter() {
+            let player = contest
+                .players
+                .iter()
+                .find(|player| player.username == aggregate.username && player.attended);
+
+            match player {
+                Some(player) => {
+                    ak.push(is_ak(player));
+                    win.push(player.local_rank == 1);
+                    bug_free.push(is_bug_free(player));
+
+                    if let Some(percentile) = percentile(contest, player) {
+                        results.push((&contest.name, percentile));
+                    }
+                }
+                None => {
+                    ak.push(false);
+                    win.push(false);
+                    bug_free.push(false);
+                }
+            }
+        }
+
+        aggregate.ak_streak = ak.longest;
+        aggregate.win_streak = win.longest;
+        aggregate.bug_free_streak = bug_free.longest;
+
+        if results.is_empty() {
+            continue;
+        }
+        let percentiles: Vec<f64> = results.iter().map(|(_, percentile)| *percentile).collect();
+        aggregate.avg_percentile = Some(percentiles.iter().sum::<f64>() / percentiles.len() as f64);
+
+        let best = results
+            .iter()
+            .min_by(|lhs, rhs| lhs.1.partial_cmp(&rhs.1).unwrap())
+            .unwrap();
+        let worst = results
+            .iter()
+            .max_by(|lhs, rhs| lhs.1.partial_cmp(&rhs.1).unwrap())
+            .unwrap();
+        aggregate.best_contest = Some(render::ContestPercentile {
+            name: best.0.to_string(),
+            percentile: best.1,
+        });
+        aggregate.worst_contest = Some(render::ContestPercentile {
+            name: worst.0.to_string(),
+            percentile: worst.1,
+        });
+
+        let skip = percentiles.len().saturating_sub(trend_window);
+        aggregate.trend = trend(&percentiles[skip..]);
+    }
+}
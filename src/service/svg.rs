@@ -0,0 +1,278 @@
+use crate::{
+    model::render,
+    service::{
+        chart::{Scale, Series},
+        render::problem_label,
+    },
+    utils::finish_time,
+};
+
+const WIDTH: f64 = 720.0;
+const MARGIN_LEFT: f64 = 80.0;
+const MARGIN_RIGHT: f64 = 160.0;
+const MARGIN_TOP: f64 = 48.0;
+const MARGIN_BOTTOM: f64 = 48.0;
+const CHART_HEIGHT: f64 = 280.0;
+const ROW_HEIGHT: f64 = 32.0;
+const COLORS: [&str; 8] = [
+    "#4e79a7", "#f28e2b", "#e15759", "#76b7b2", "#59a14f", "#edc948", "#b07aa1", "#ff9da7",
+];
+
+fn escape(text: &str) -> String {
+    return text
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;");
+}
+
+fn header(height: f64, title: &str) -> String {
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" font-family=\"sans-serif\" font-size=\"12\">\n",
+        w = WIDTH,
+        h = height
+    );
+    svg += &format!(
+        "<rect width=\"{}\" height=\"{}\" fill=\"#ffffff\"/>\n",
+        WIDTH, height
+    );
+    svg += &format!(
+        "<text x=\"{}\" y=\"28\" font-size=\"16\" font-weight=\"bold\">{}</text>\n",
+        MARGIN_LEFT,
+        escape(title)
+    );
+    return svg;
+}
+
+fn legend(svg: &mut String, idx: usize, username: &str) {
+    let x = WIDTH - MARGIN_RIGHT + 16.0;
+    let y = MARGIN_TOP + 8.0 + idx as f64 * 20.0;
+    *svg += &format!(
+        "<rect x=\"{}\" y=\"{}\" width=\"12\" height=\"12\" fill=\"{}\"/>\n",
+        x,
+        y - 10.0,
+        COLORS[idx % COLORS.len()]
+    );
+    *svg += &format!(
+        "<text x=\"{}\" y=\"{}\">{}</text>\n",
+        x + 18.0,
+        y,
+        escape(username)
+    );
+}
+
+pub fn line_chart(
+    title: &str,
+    labels: &[String],
+    series: &[Series],
+    log: bool,
+    higher_is_better: bool,
+) -> Option<String> {
+    let scale = Scale::new(series, log, higher_is_better)?;
+    let plot_width = WIDTH - MARGIN_LEFT - MARGIN_RIGHT;
+    let step = plot_width / (labels.len().max(2) - 1) as f64;
+    let x_of = |column: usize| MARGIN_LEFT + column as f64 * step;
+    let y_of = |value: f64| MARGIN_TOP + (1.0 - scale.fraction(value)) * CHART_HEIGHT;
+
+    let mut svg = header(MARGIN_TOP + CHART_HEIGHT + MARGIN_BOTTOM, title);
+
+    // horizontal grid with value ticks, best values on top
+    for tick in 0..5 {
+        let fraction = 1.0 - tick as f64 / 4.0;
+        let y = MARGIN_TOP + tick as f64 / 4.0 * CHART_HEIGHT;
+        svg += &format!(
+            "<line x1=\"{}\" y1=\"{y}\" x2=\"{}\" y2=\"{y}\" stroke=\"#dddddd\"/>\n",
+            MARGIN_LEFT,
+            MARGIN_LEFT + plot_width,
+            y = y
+        );
+        svg += &format!(
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"end\">{:.0}</text>\n",
+            MARGIN_LEFT - 8.0,
+            y + 4.0,
+            scale.value(fraction)
+        );
+    }
+    for (column, label) in labels.iter().enumerate() {
+        svg += &format!(
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\">{}</text>\n",
+            x_of(column),
+            MARGIN_TOP + CHART_HEIGHT + 20.0,
+            escape(label)
+        );
+    }
+
+    for (idx, series) in series.iter().enumerate() {
+        let color = COLORS[idx % COLORS.len()];
+
+        // a missed contest breaks the line
+        let mut segments = vec![vec![]];
+        for (column, value) in series.values.iter().enumerate() {
+            match value {
+                Some(value) if !log || *value > 0.0 => {
+                    segments
+                        .last_mut()
+                        .unwrap()
+                        .push((x_of(column), y_of(*value), *value));
+                }
+                _ => segments.push(vec![]),
+            }
+        }
+
+        for segment in segments.iter().filter(|segment| !segment.is_empty()) {
+            let points: Vec<String> = segment
+                .iter()
+                .map(|(x, y, _)| format!("{:.1},{:.1}", x, y))
+                .collect();
+            svg += &format!(
+                "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"2\"/>\n",
+                points.join(" "),
+                color
+            );
+            for (x, y, value) in segment.iter() {
+                svg += &format!(
+                    "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"4\" fill=\"{}\"><title>{} {:.0}</title></circle>\n",
+                    x,
+                    y,
+                    color,
+                    escape(&series.username),
+                    value
+                );
+            }
+        }
+        legend(&mut svg, idx, &series.username);
+    }
+
+    svg += "</svg>\n";
+    return Some(svg);
+}
+
+pub fn contest_timeline(
+    contest: &render::Contest,
+    options: &render::RenderOptions,
+) -> Option<String> {
+    let players: Vec<&render::User> = contest
+        .players
+        .iter()
+        .filter(|player| player.attended && player.is_friend && !player.submissions.is_empty())
+        .collect();
+    if players.is_empty() {
+        return None;
+    }
+
+    // round the axis up to the next ten minutes
+    let end = players
+        .iter()
+        .flat_map(|player| player.submissions.iter())
+        .filter(|submission| submission.status == render::SubmissionStatus::Accepted)
+        .map(|submission| submission.finish_time)
+        .chain(players.iter().map(|player| player.finish_time))
+        .chain(
+            players
+                .iter()
+                .flat_map(|player| player.submissions.iter())
+                .flat_map(|submission| submission.attempts.iter())
+                .map(|attempt| attempt.time),
+        )
+        .max()
+        .unwrap_or(0);
+    let end = ((end + 599) / 600).max(1) * 600;
+
+    let plot_width = WIDTH - MARGIN_LEFT - MARGIN_RIGHT;
+    let x_of = |time: i64| MARGIN_LEFT + time as f64 / end as f64 * plot_width;
+    let height = MARGIN_TOP + players.len() as f64 * ROW_HEIGHT + MARGIN_BOTTOM;
+    let mut svg = header(height, &contest.name);
+
+    let axis_y = MARGIN_TOP + players.len() as f64 * ROW_HEIGHT;
+    for tick in 0..=(end / 600) {
+        let x = x_of(tick * 600);
+        svg += &format!(
+            "<line x1=\"{x}\" y1=\"{}\" x2=\"{x}\" y2=\"{}\" stroke=\"#dddddd\"/>\n",
+            MARGIN_TOP,
+            axis_y,
+            x = x
+        );
+        svg += &format!(
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\">{}</text>\n",
+            x,
+            axis_y + 20.0,
+            tick * 10
+        );
+    }
+    svg += &format!(
+        "<text x=\"{}\" y=\"{}\" text-anchor=\"end\">min</text>\n",
+        MARGIN_LEFT + plot_width,
+        axis_y + 36.0
+    );
+
+    for (row, player) in players.iter().enumerate() {
+        let y = MARGIN_TOP + row as f64 * ROW_HEIGHT;
+        svg += &format!(
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"end\">{}</text>\n",
+            MARGIN_LEFT - 8.0,
+            y + ROW_HEIGHT / 2.0 + 4.0,
+            escape(&options.display(&player.username))
+        );
+
+        // each solve fills the span since the previous one
+        let mut solved: Vec<(usize, &render::Submission)> = player
+            .submissions
+            .iter()
+            .enumerate()
+            .filter(|(_, submission)| submission.status == render::SubmissionStatus::Accepted)
+            .collect();
+        solved.sort_by_key(|(_, submission)| submission.finish_time);
+
+        let mut previous = 0;
+        for (tid, submission) in solved.iter() {
+            let label = problem_label(&submission.title, &submission.problem, options.full_title);
+            svg += &format!(
+                "<rect x=\"{:.1}\" y=\"{}\" width=\"{:.1}\" height=\"{}\" fill=\"{}\"><title>{} {}</title></rect>\n",
+                x_of(previous),
+                y + 4.0,
+                x_of(submission.finish_time) - x_of(previous),
+                ROW_HEIGHT - 8.0,
+                COLORS[tid % COLORS.len()],
+                escape(&label),
+                finish_time::seconds_to_finish_time(submission.finish_time)
+            );
+            svg += &format!(
+                "<text x=\"{:.1}\" y=\"{}\" text-anchor=\"end\" fill=\"#ffffff\">{}</text>\n",
+                x_of(submission.finish_time) - 4.0,
+                y + ROW_HEIGHT / 2.0 + 4.0,
+                escape(&submission.title)
+            );
+            previous = submission.finish_time;
+        }
+
+        // wrong attempts, only known when attempts are fetched
+        for submission in player.submissions.iter() {
+            for attempt in submission
+                .attempts
+                .iter()
+                .filter(|attempt| attempt.verdict != "Accepted")
+            {
+                svg += &format!(
+                    "<line x1=\"{x:.1}\" y1=\"{}\" x2=\"{x:.1}\" y2=\"{}\" stroke=\"#d62728\" stroke-width=\"2\"><title>{} {} {}</title></line>\n",
+                    y + 2.0,
+                    y + ROW_HEIGHT - 2.0,
+                    escape(&submission.title),
+                    escape(&attempt.verdict),
+                    finish_time::seconds_to_finish_time(attempt.time),
+                    x = x_of(attempt.time)
+                );
+            }
+        }
+    }
+
+    for (tid, question) in players[0].submissions.iter().enumerate() {
+        legend(
+            &mut svg,
+            tid,
+            &problem_label(&question.title, &question.problem, options.full_title),
+        );
+    }
+
+    svg += "</svg>\n";
+    return Some(svg);
+}
@@ -0,0 +1,222 @@
+use crate::model::render::Theme;
+use unicode_width::UnicodeWidthStr;
+
+// every decoration the text renderer prints, as (emoji, plain)
+#[derive(Clone, Copy)]
+pub enum Glyph {
+    Live,
+    Contest,
+    Absent,
+    Guest,
+    Country,
+    FinishTime,
+    GlobalRank,
+    Score,
+    Accepted,
+    Unaccepted,
+    Pending,
+    SubmitTime,
+    Bug,
+    BugFree,
+    AkBugFree,
+    Ak,
+    AttemptTime,
+    Problems,
+    Solved,
+    FirstSolver,
+    Fastest,
+    Median,
+    BugFreeRate,
+    Overall,
+    Wins,
+    Attended,
+    TotalTime,
+    AkStreak,
+    WinStreak,
+    BugFreeStreak,
+    AvgTop,
+    Trend,
+    Best,
+    Worst,
+    Season,
+    Points,
+    Dropped,
+    Countries,
+    Members,
+    Teams,
+    TeamStandings,
+    User,
+    Rating,
+    Versus,
+    Summary,
+    Matrix,
+    Chart,
+    SolveTimes,
+    Upcoming,
+    Recent,
+    Duration,
+}
+
+fn glyph_text(glyph: Glyph) -> (&'static str, &'static str) {
+    match glyph {
+        Glyph::Live => ("🎦", ""),
+        Glyph::Contest => ("🏆", "== "),
+        Glyph::Absent => ("💤", "-   "),
+        Glyph::Guest => ("👀", "*   "),
+        Glyph::Country => ("🌏", ""),
+        Glyph::FinishTime => ("🍺", "time "),
+        Glyph::GlobalRank => ("📊", "rank "),
+        Glyph::Score => ("✨", "score "),
+        Glyph::Accepted => ("✅", "[AC] "),
+        Glyph::Unaccepted => ("❌", "[NO] "),
+        Glyph::Pending => ("⏳", "[..] "),
+        Glyph::SubmitTime => ("⏰", "at "),
+        Glyph::Bug => ("🐛", "bugs "),
+        Glyph::BugFree => ("🌟BUG FREE", "BUG FREE"),
+        Glyph::AkBugFree => ("🐂AK", "AK*"),
+        Glyph::Ak => ("🎉AK", "AK"),
+        Glyph::AttemptTime => ("🕒", ""),
+        Glyph::Problems => ("📋Problems", "Problems"),
+        Glyph::Solved => ("✅", "solved "),
+        Glyph::FirstSolver => ("🥇", "first "),
+        Glyph::Fastest => ("⚡️", "fastest "),
+        Glyph::Median => ("⏱️", "median "),
+        Glyph::BugFreeRate => ("🌟", "bug free "),
+        Glyph::Overall => ("🍎Overall Data", "== Overall Data"),
+        Glyph::Wins => ("🏅️", "wins "),
+        Glyph::Attended => ("⚡️", "attended "),
+        Glyph::TotalTime => ("⏰", "time "),
+        Glyph::AkStreak => ("🔥AK x", "streaks AK x"),
+        Glyph::WinStreak => ("🏅️Win x", "Win x"),
+        Glyph::BugFreeStreak => ("🌟Bug free x", "Bug free x"),
+        Glyph::AvgTop => ("📊Top ", "avg top "),
+        Glyph::Trend => ("📈", "trend "),
+        Glyph::Best => ("⬆️", "best "),
+        Glyph::Worst => ("⬇️", "worst "),
+        Glyph::Season => ("🏁Season ", "== Season "),
+        Glyph::Points => ("🏁", "points "),
+        Glyph::Dropped => ("🗑️", "dropped "),
+        Glyph::Countries => ("🌏Countries", "== Countries"),
+        Glyph::Members => ("👥", "users "),
+        Glyph::Teams => ("👥Teams", "Teams"),
+        Glyph::TeamStandings => ("👥Team Standings", "== Team Standings"),
+        Glyph::User => ("👤", "== "),
+        Glyph::Rating => ("📈", "rating "),
+        Glyph::Versus => ("⚔️", "== "),
+        Glyph::Summary => ("📊", "== "),
+        Glyph::Matrix => ("🧮", "== "),
+        Glyph::Chart => ("📈", "== "),
+        Glyph::SolveTimes => ("📊", "== "),
+        Glyph::Upcoming => ("🗓️Upcoming", "== Upcoming"),
+        Glyph::Recent => ("🏁Recent", "== Recent"),
+        Glyph::Duration => ("⏰", "for "),
+    }
+}
+
+// ANSI SGR parameters for the color theme
+fn glyph_color(glyph: Glyph) -> Option<&'static str> {
+    match glyph {
+        Glyph::Contest
+        | Glyph::Overall
+        | Glyph::Season
+        | Glyph::Countries
+        | Glyph::TeamStandings
+        | Glyph::User
+        | Glyph::Versus
+        | Glyph::Summary
+        | Glyph::Matrix
+        | Glyph::Chart
+        | Glyph::SolveTimes
+        | Glyph::Upcoming
+        | Glyph::Recent => Some("1;36"),
+        Glyph::Accepted | Glyph::Solved | Glyph::BugFree | Glyph::BugFreeRate => Some("32"),
+        Glyph::AkBugFree | Glyph::Ak => Some("1;32"),
+        Glyph::Unaccepted | Glyph::Bug => Some("31"),
+        Glyph::Pending => Some("33"),
+        Glyph::Absent | Glyph::Guest => Some("2"),
+        Glyph::Live => Some("1;35"),
+        _ => None,
+    }
+}
+
+impl Theme {
+    pub fn glyph(&self, glyph: Glyph) -> String {
+        let (emoji, plain) = glyph_text(glyph);
+        match self {
+            Theme::Emoji => return emoji.to_string(),
+            Theme::Ascii => return plain.to_string(),
+            Theme::Color => match glyph_color(glyph) {
+                Some(color) if !plain.is_empty() => return paint(plain, color),
+                _ => return plain.to_string(),
+            },
+        }
+    }
+
+    // always 2 columns wide for emoji, 4 for plain themes
+    pub fn medal(&self, local_rank: u32) -> String {
+        let medal = match local_rank {
+            1 => "🏅️",
+            2 => "🥈",
+            3 => "🥉",
+            _ => "👴",
+        };
+        let rank = format!("#{:<3}", local_rank);
+
+        match self {
+            Theme::Emoji => return medal.to_string(),
+            Theme::Ascii => return rank,
+            Theme::Color => match local_rank {
+                1 => return paint(&rank, "1;33"),
+                2 => return paint(&rank, "1;37"),
+                3 => return paint(&rank, "33"),
+                _ => return rank,
+            },
+        }
+    }
+}
+
+fn paint(text: &str, color: &str) -> String {
+    return format!("\x1b[{}m{}\x1b[0m", color, text);
+}
+
+// "auto" picks emoji on a terminal and plain ASCII for pipes and log files
+pub fn select(name: &str) -> Theme {
+    match name {
+        "emoji" => return Theme::Emoji,
+        "ascii" => return Theme::Ascii,
+        "color" => return Theme::Color,
+        "auto" => {}
+        _ => println!("[WARN] Unknown theme, use auto instead, theme={}", name),
+    }
+
+    if atty::is(atty::Stream::Stdout) {
+        return Theme::Emoji;
+    }
+    return Theme::Ascii;
+}
+
+// display width, ANSI color sequences take no space
+pub fn width(text: &str) -> usize {
+    let mut width = 0;
+    let mut chunks = text.split('\x1b');
+    if let Some(head) = chunks.next() {
+        width += UnicodeWidthStr::width(head);
+    }
+    for chunk in chunks {
+        let visible = match chunk.find('m') {
+            Some(end) if chunk.starts_with('[') => &chunk[end + 1..],
+            _ => chunk,
+        };
+        width += UnicodeWidthStr::width(visible);
+    }
+    return width;
+}
+
+// left align by display width, wide characters take two columns
+pub fn pad(text: &str, width: usize) -> String {
+    let text_width = self::width(text);
+    if text_width >= width {
+        return text.to_string();
+    }
+    return format!("{}{}", text, " ".repeat(width - text_width));
+}
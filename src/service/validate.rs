@@ -0,0 +1,273 @@
+use crate::{
+    model::config::{Config, ProfileConfig, SeasonConfig, ViewConfig},
+    service::handler,
+};
+use chrono::{Locale, NaiveDate};
+use chrono_tz::Tz;
+use std::{collections::BTreeMap, convert::TryFrom};
+
+const THEMES: [&str; 4] = ["auto", "emoji", "ascii", "color"];
+const COLUMNS: [&str; 5] = ["country", "rank", "score", "time", "ak"];
+const SORT_KEYS: [&str; 6] = ["rank", "score", "time", "global_rank", "name", "country"];
+
+#[derive(PartialEq)]
+pub enum Severity {
+    // falls back to a default at runtime
+    Warning,
+    // breaks fetching or rendering
+    Error,
+}
+
+pub struct Issue {
+    pub severity: Severity,
+    // json path like leetcode.contests[1]
+    pub location: String,
+    pub message: String,
+}
+
+impl Issue {
+    pub fn print(&self) {
+        match self.severity {
+            Severity::Warning => println!("[WARN] {}: {}", self.location, self.message),
+            Severity::Error => println!("[ERROR] {}: {}", self.location, self.message),
+        }
+    }
+}
+
+fn push(issues: &mut Vec<Issue>, severity: Severity, location: String, message: String) {
+    issues.push(Issue {
+        severity,
+        location,
+        message,
+    });
+}
+
+fn is_contest_id(contest_id: &str) -> bool {
+    return (contest_id.starts_with('w') || contest_id.starts_with('b'))
+        && contest_id[1..].parse::<u32>().is_ok();
+}
+
+fn check_contests(issues: &mut Vec<Issue>, location: &str, contests: &[String]) {
+    for (idx, contest_id) in contests.iter().enumerate() {
+        if !is_contest_id(contest_id) {
+            push(
+                issues,
+                Severity::Error,
+                format!("{}[{}]", location, idx),
+                format!(
+                    "invalid contest id \"{}\", expected w<number> or b<number>",
+                    contest_id
+                ),
+            );
+        } else if let Some(first) = contests.iter().position(|other| other == contest_id) {
+            if first < idx {
+                push(
+                    issues,
+                    Severity::Error,
+                    format!("{}[{}]", location, idx),
+                    format!(
+                        "duplicate contest \"{}\", first at {}[{}]",
+                        contest_id, location, first
+                    ),
+                );
+            }
+        }
+    }
+}
+
+fn check_users(issues: &mut Vec<Issue>, location: &str, users: &[String]) {
+    for (idx, username) in users.iter().enumerate() {
+        if username.trim().is_empty() {
+            push(
+                issues,
+                Severity::Error,
+                format!("{}[{}]", location, idx),
+                String::from("empty username"),
+            );
+        } else if let Some(first) = users.iter().position(|other| other == username) {
+            if first < idx {
+                push(
+                    issues,
+                    Severity::Error,
+                    format!("{}[{}]", location, idx),
+                    format!(
+                        "duplicate user \"{}\", first at {}[{}]",
+                        username, location, first
+                    ),
+                );
+            }
+        }
+    }
+}
+
+fn check_theme(issues: &mut Vec<Issue>, location: &str, theme: &str) {
+    if !THEMES.contains(&theme) {
+        push(
+            issues,
+            Severity::Warning,
+            location.to_string(),
+            format!(
+                "unknown theme \"{}\", expected one of {}",
+                theme,
+                THEMES.join(", ")
+            ),
+        );
+    }
+}
+
+fn check_views(issues: &mut Vec<Issue>, location: &str, views: &BTreeMap<String, ViewConfig>) {
+    for (name, view) in views.iter() {
+        for (idx, column) in view.columns.iter().enumerate() {
+            if !COLUMNS.contains(&column.trim()) {
+                push(
+                    issues,
+                    Severity::Warning,
+                    format!("{}.{}.columns[{}]", location, name, idx),
+                    format!(
+                        "unknown column \"{}\", expected one of {}",
+                        column,
+                        COLUMNS.join(", ")
+                    ),
+                );
+            }
+        }
+        if !SORT_KEYS.contains(&view.sort.as_str()) {
+            push(
+                issues,
+                Severity::Warning,
+                format!("{}.{}.sort", location, name),
+                format!(
+                    "unknown sort key \"{}\", expected one of {}",
+                    view.sort,
+                    SORT_KEYS.join(", ")
+                ),
+            );
+        }
+    }
+}
+
+fn check_seasons(issues: &mut Vec<Issue>, location: &str, seasons: &[SeasonConfig]) {
+    for (idx, season) in seasons.iter().enumerate() {
+        let location = format!("{}[{}]", location, idx);
+        check_contests(issues, &format!("{}.contests", location), &season.contests);
+        for (key, date) in [("start", &season.start), ("end", &season.end)].iter() {
+            if !date.is_empty() && NaiveDate::parse_from_str(date, "%Y-%m-%d").is_err() {
+                push(
+                    issues,
+                    Severity::Warning,
+                    format!("{}.{}", location, key),
+                    format!("invalid date \"{}\", expected YYYY-MM-DD", date),
+                );
+            }
+        }
+    }
+}
+
+fn check_profile(issues: &mut Vec<Issue>, name: &str, profile: &ProfileConfig) {
+    let location = format!("profiles.{}", name);
+    check_users(issues, &format!("{}.users", location), &profile.users);
+    check_contests(issues, &format!("{}.contests", location), &profile.contests);
+    check_users(
+        issues,
+        &format!("{}.live_users", location),
+        &profile.live_users,
+    );
+    check_contests(
+        issues,
+        &format!("{}.live_contests", location),
+        &profile.live_contests,
+    );
+    if let Some(theme) = &profile.theme {
+        check_theme(issues, &format!("{}.theme", location), theme);
+    }
+    if let Some(views) = &profile.views {
+        check_views(issues, &format!("{}.views", location), views);
+    }
+    if let Some(seasons) = &profile.seasons {
+        check_seasons(issues, &format!("{}.seasons", location), seasons);
+    }
+}
+
+// every problem of the config, in file order
+pub fn validate(config: &Config) -> Vec<Issue> {
+    let mut issues = Vec::<Issue>::new();
+
+    if !config.website.is_empty() && !handler::handler_hashmap().contains_key(&config.website) {
+        push(
+            &mut issues,
+            Severity::Error,
+            String::from("website"),
+            format!("unknown website \"{}\"", config.website),
+        );
+    }
+
+    let leetcode = &config.leetcode;
+    check_users(&mut issues, "leetcode.users", &leetcode.users);
+    check_contests(&mut issues, "leetcode.contests", &leetcode.contests);
+    check_users(&mut issues, "leetcode.live_users", &leetcode.live_users);
+    check_contests(
+        &mut issues,
+        "leetcode.live_contests",
+        &leetcode.live_contests,
+    );
+    if leetcode.max_rank == 0 {
+        push(
+            &mut issues,
+            Severity::Error,
+            String::from("leetcode.max_rank"),
+            String::from("must be at least 1"),
+        );
+    }
+    if leetcode.concurrent == 0 {
+        push(
+            &mut issues,
+            Severity::Error,
+            String::from("leetcode.concurrent"),
+            String::from("must be at least 1, no ranking page would be fetched"),
+        );
+    }
+    if config.live.interval == 0 {
+        push(
+            &mut issues,
+            Severity::Error,
+            String::from("live.interval"),
+            String::from("must be at least 1 second"),
+        );
+    }
+
+    check_seasons(&mut issues, "seasons", &config.seasons);
+
+    if Locale::try_from(config.date.locale.as_str()).is_err() {
+        push(
+            &mut issues,
+            Severity::Warning,
+            String::from("date.locale"),
+            format!("unknown locale \"{}\"", config.date.locale),
+        );
+    }
+    if !config.date.timezone.is_empty() && config.date.timezone.parse::<Tz>().is_err() {
+        push(
+            &mut issues,
+            Severity::Warning,
+            String::from("date.timezone"),
+            format!("unknown timezone \"{}\"", config.date.timezone),
+        );
+    }
+    check_theme(&mut issues, "theme", &config.theme);
+    check_views(&mut issues, "views", &config.views);
+
+    for (name, profile) in config.profiles.iter() {
+        check_profile(&mut issues, name, profile);
+    }
+    if !config.current_profile.is_empty() && !config.profiles.contains_key(&config.current_profile)
+    {
+        push(
+            &mut issues,
+            Severity::Warning,
+            String::from("current_profile"),
+            format!("profile \"{}\" doesn't exist", config.current_profile),
+        );
+    }
+
+    return issues;
+}
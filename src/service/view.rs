@@ -0,0 +1,100 @@
+use crate::model::{config::ViewConfig, render};
+use std::cmp::Ordering;
+
+fn parse_column(name: &str) -> Option<render::Column> {
+    match name {
+        "country" => return Some(render::Column::Country),
+        "rank" => return Some(render::Column::GlobalRank),
+        "score" => return Some(render::Column::Score),
+        "time" => return Some(render::Column::FinishTime),
+        "ak" => return Some(render::Column::Ak),
+        _ => {
+            println!("[WARN] Unknown column ignored, column={}", name);
+            return None;
+        }
+    }
+}
+
+fn parse_sort(name: &str) -> render::SortKey {
+    match name {
+        "rank" => return render::SortKey::LocalRank,
+        "score" => return render::SortKey::Score,
+        "time" => return render::SortKey::FinishTime,
+        "global_rank" => return render::SortKey::GlobalRank,
+        "name" => return render::SortKey::Username,
+        "country" => return render::SortKey::Country,
+        _ => {
+            println!(
+                "[WARN] Unknown sort key, sort by rank instead, sort={}",
+                name
+            );
+            return render::SortKey::LocalRank;
+        }
+    }
+}
+
+pub fn resolve(config: &ViewConfig) -> render::View {
+    return render::View {
+        columns: config
+            .columns
+            .iter()
+            .filter_map(|column| parse_column(column.trim()))
+            .collect(),
+        sort: parse_sort(&config.sort),
+        users: config.users.clone(),
+        countries: config.countries.clone(),
+        top: config.top,
+    };
+}
+
+fn compare(lhs: &render::User, rhs: &render::User, sort: render::SortKey) -> Ordering {
+    match sort {
+        // players already come in contest order, non-friends included
+        render::SortKey::LocalRank => return Ordering::Equal,
+        render::SortKey::Score => return rhs.score.cmp(&lhs.score),
+        render::SortKey::FinishTime => return lhs.finish_time.cmp(&rhs.finish_time),
+        render::SortKey::GlobalRank => return lhs.global_rank.cmp(&rhs.global_rank),
+        render::SortKey::Username => {
+            return lhs
+                .username
+                .to_lowercase()
+                .cmp(&rhs.username.to_lowercase())
+        }
+        render::SortKey::Country => return lhs.country.cmp(&rhs.country),
+    }
+}
+
+// countries are matched case-insensitively, --only-countries china is China
+pub fn is_visible(view: &render::View, username: &str, country: &str) -> bool {
+    let country = country.to_lowercase();
+    return (view.users.is_empty() || view.users.iter().any(|user| user == username))
+        && (view.countries.is_empty()
+            || view
+                .countries
+                .iter()
+                .any(|visible| visible.to_lowercase() == country));
+}
+
+// players of one contest as the view shows them, absent players stay last
+pub fn select_players<'a>(
+    players: &'a [render::User],
+    view: &render::View,
+) -> Vec<&'a render::User> {
+    let mut selected: Vec<&render::User> = players
+        .iter()
+        .filter(|player| is_visible(view, &player.username, &player.country))
+        .collect();
+
+    // stable, ties keep the contest order
+    selected.sort_by(|lhs, rhs| {
+        if lhs.attended != rhs.attended {
+            return rhs.attended.cmp(&lhs.attended);
+        }
+        return compare(lhs, rhs, view.sort);
+    });
+
+    if view.top > 0 {
+        selected.truncate(view.top);
+    }
+    return selected;
+}
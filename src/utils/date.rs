@@ -0,0 +1,134 @@
+use crate::model::config::DateConfig;
+use chrono::{
+    format::{Item, StrftimeItems},
+    Local, Locale, NaiveDate, TimeZone,
+};
+use chrono_tz::Tz;
+use std::{convert::TryFrom, env};
+
+// unix seconds used as the current time, keeps output reproducible
+const NOW_ENV: &str = "CONTEST_RANK_NOW";
+
+#[derive(Clone)]
+pub struct DateFormatter {
+    locale: Locale,
+    // None is the system local timezone
+    timezone: Option<Tz>,
+    format: String,
+    // fixed clock from $CONTEST_RANK_NOW, None is the system clock
+    fixed_now: Option<i64>,
+}
+
+impl Default for DateFormatter {
+    fn default() -> Self {
+        return DateFormatter::new(&DateConfig::default());
+    }
+}
+
+impl DateFormatter {
+    // invalid values fall back to the defaults with a warning
+    pub fn new(config: &DateConfig) -> Self {
+        let default_config = DateConfig::default();
+
+        let locale = match Locale::try_from(config.locale.as_str()) {
+            Ok(locale) => locale,
+            Err(_) => {
+                println!(
+                    "[WARN] Unknown locale, use {} instead, locale={}",
+                    default_config.locale, config.locale
+                );
+                Locale::try_from(default_config.locale.as_str()).unwrap()
+            }
+        };
+
+        let timezone = if config.timezone.is_empty() {
+            None
+        } else {
+            match config.timezone.parse::<Tz>() {
+                Ok(timezone) => Some(timezone),
+                Err(err) => {
+                    println!(
+                        "[WARN] Unknown timezone, use local timezone instead, timezone={}, err={}",
+                        config.timezone, err
+                    );
+                    None
+                }
+            }
+        };
+
+        let format = if StrftimeItems::new(&config.format).any(|item| item == Item::Error) {
+            println!(
+                "[WARN] Invalid date format, use {} instead, format={}",
+                default_config.format, config.format
+            );
+            default_config.format
+        } else {
+            config.format.clone()
+        };
+
+        let fixed_now = match env::var(NOW_ENV) {
+            Ok(now) => match now.parse::<i64>() {
+                Ok(now) => Some(now),
+                Err(_) => {
+                    println!(
+                        "[WARN] Invalid ${}, use the system clock, value={}",
+                        NOW_ENV, now
+                    );
+                    None
+                }
+            },
+            Err(_) => None,
+        };
+
+        return DateFormatter {
+            locale,
+            timezone,
+            format,
+            fixed_now,
+        };
+    }
+
+    pub fn format(&self, timestamp: i64) -> String {
+        match self.timezone {
+            Some(timezone) => {
+                return timezone
+                    .timestamp(timestamp, 0)
+                    .format_localized(&self.format, self.locale)
+                    .to_string();
+            }
+            None => {
+                return Local
+                    .timestamp(timestamp, 0)
+                    .format_localized(&self.format, self.locale)
+                    .to_string();
+            }
+        }
+    }
+
+    pub fn timestamp(&self) -> i64 {
+        return self.fixed_now.unwrap_or_else(|| Local::now().timestamp());
+    }
+
+    pub fn now(&self) -> String {
+        return self.format(self.timestamp());
+    }
+
+    // midnight of the given day in the configured timezone
+    pub fn start_of_day(&self, date: NaiveDate) -> Option<i64> {
+        let midnight = date.and_hms(0, 0, 0);
+        match self.timezone {
+            Some(timezone) => {
+                return timezone
+                    .from_local_datetime(&midnight)
+                    .earliest()
+                    .map(|date| date.timestamp());
+            }
+            None => {
+                return Local
+                    .from_local_datetime(&midnight)
+                    .earliest()
+                    .map(|date| date.timestamp());
+            }
+        }
+    }
+}
@@ -1,3 +1,4 @@
+pub mod date;
 pub mod finish_time;
 pub mod null;
 pub mod request;
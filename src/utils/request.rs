@@ -1,37 +1,570 @@
-use serde::de::DeserializeOwned;
-use std::io;
+use crate::model::config::{Credentials, FixtureMode, RequestConfig};
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use reqwest::{
+    header::{self, HeaderMap, HeaderName, HeaderValue},
+    StatusCode,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    io,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+use tokio::fs;
 
-const MAX_RETRY_COUNT: u32 = 3;
-const USER_AGENT: &'static str = "Mozilla/5.0 (Macintosh; Intel Mac OS X 11_2_0) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/88.0.4324.146 Safari/537.36";
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
 
-async fn _send_one_request<T>(url: &str) -> Result<T, Box<dyn std::error::Error>>
-where
-    T: DeserializeOwned,
-{
-    let client = reqwest::Client::builder().user_agent(USER_AGENT).build()?;
-    let resp = client.get(url).send().await?;
-    return Ok(resp.json::<T>().await?);
+pub struct RateLimiter {
+    rate: f64,
+    capacity: f64,
+    bucket: Mutex<TokenBucket>,
 }
 
-pub async fn send_request<T>(url: &str) -> Result<T, Box<dyn std::error::Error>>
-where
-    T: DeserializeOwned,
-{
-    for retry_idx in 1u32..=MAX_RETRY_COUNT {
-        match _send_one_request::<T>(url).await {
-            Ok(resp) => return Ok(resp),
+impl RateLimiter {
+    pub fn new(rate: u32, burst: u32) -> RateLimiter {
+        let capacity = burst.max(1) as f64;
+        return RateLimiter {
+            rate: rate as f64,
+            capacity,
+            bucket: Mutex::new(TokenBucket {
+                tokens: capacity,
+                last_refill: Instant::now(),
+            }),
+        };
+    }
+
+    // returns how long the caller has to wait before a token is available
+    fn try_acquire(&self, now: Instant) -> Option<Duration> {
+        let mut bucket = self.bucket.lock().unwrap();
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.rate).min(self.capacity);
+        bucket.last_refill = now;
 
-            Err(err) => {
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            return None;
+        }
+        return Some(Duration::from_secs_f64((1.0 - bucket.tokens) / self.rate));
+    }
+
+    pub async fn acquire(&self) {
+        // rate_limit = 0 means unlimited
+        if self.rate <= 0.0 {
+            return;
+        }
+
+        while let Some(wait) = self.try_acquire(Instant::now()) {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+pub struct Requester {
+    // a client that failed to build fails every request, like the error
+    // from building one per request used to
+    client: Result<reqwest::Client, String>,
+    limiter: RateLimiter,
+    config: RequestConfig,
+    verbose: bool,
+}
+
+enum RequestError {
+    // server asked us to slow down, optionally with an explicit delay
+    Throttled(Option<Duration>),
+    // connection errors, timeouts and 5xx, may pass on the next try
+    Retryable(Box<dyn std::error::Error>),
+    // other 4xx and undecodable bodies fail the same way every time
+    Fatal(Box<dyn std::error::Error>),
+}
+
+impl From<reqwest::Error> for RequestError {
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_connect() || err.is_timeout() || err.is_request() || err.is_body() {
+            return RequestError::Retryable(Box::new(err));
+        }
+        return RequestError::Fatal(Box::new(err));
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Fixture {
+    url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    payload: Option<serde_json::Value>,
+    body: serde_json::Value,
+}
+
+// FNV-1a, stable across builds so fixture names never change
+fn fnv1a(data: &str) -> u64 {
+    let mut hash = 0xcbf29ce484222325u64;
+    for byte in data.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    return hash;
+}
+
+fn fixture_path(dir: &str, url: &str, payload: Option<&serde_json::Value>) -> PathBuf {
+    let name: String = url
+        .trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let name = name.trim_end_matches('_');
+
+    match payload {
+        Some(payload) => {
+            let hash = fnv1a(&payload.to_string());
+            return Path::new(dir).join(format!("{}_{:016x}.json", name, hash));
+        }
+        None => return Path::new(dir).join(format!("{}.json", name)),
+    }
+}
+
+async fn read_fixture(
+    dir: &str,
+    url: &str,
+    payload: Option<&serde_json::Value>,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let path = fixture_path(dir, url, payload);
+    let content = match fs::read_to_string(&path).await {
+        Ok(content) => content,
+        Err(err) => {
+            return Err(Box::new(io::Error::new(
+                err.kind(),
+                format!(
+                    "fixture not found in replay mode, url={}, path={}",
+                    url,
+                    path.display()
+                ),
+            )));
+        }
+    };
+
+    let fixture = serde_json::from_str::<Fixture>(&content)?;
+    if fixture.url != url || fixture.payload.as_ref() != payload {
+        return Err(Box::new(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "fixture url mismatch, expected={}, found={}, path={}",
+                url,
+                fixture.url,
+                path.display()
+            ),
+        )));
+    }
+    return Ok(fixture.body.to_string());
+}
+
+async fn write_fixture(dir: &str, url: &str, payload: Option<&serde_json::Value>, body: &str) {
+    let path = fixture_path(dir, url, payload);
+    if let Err(err) = fs::create_dir_all(dir).await {
+        println!(
+            "[WARN] Fixture path cannot be created, path={}, e={}",
+            dir, err
+        );
+        return;
+    }
+
+    let fixture = match serde_json::from_str(body) {
+        Ok(body) => Fixture {
+            url: url.to_string(),
+            payload: payload.cloned(),
+            body,
+        },
+        Err(err) => {
+            println!(
+                "[WARN] Response is not JSON, skip recording url={}, e={}",
+                url, err
+            );
+            return;
+        }
+    };
+
+    match serde_json::to_string_pretty(&fixture) {
+        Ok(content) => {
+            if let Err(err) = fs::write(&path, content).await {
                 println!(
-                    "[Error] When send_request, retry_idx=#{}, e={}",
-                    retry_idx, err
+                    "[ERROR] When writing fixture file, path={}, e={}",
+                    path.display(),
+                    err
                 );
             }
         }
+        Err(err) => {
+            println!(
+                "[WARN] Error when serializing fixture url={}, e={}",
+                url, err
+            );
+        }
+    }
+}
+
+fn insert_header(headers: &mut HeaderMap, name: &str, value: &str) {
+    match (
+        HeaderName::from_bytes(name.as_bytes()),
+        HeaderValue::from_str(value),
+    ) {
+        (Ok(name), Ok(value)) => {
+            headers.insert(name, value);
+        }
+        _ => {
+            println!("[WARN] Invalid request header, ignored, name={}", name);
+        }
+    }
+}
+
+fn build_headers(config: &RequestConfig, credentials: &Credentials) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    for (name, value) in config.headers.iter() {
+        insert_header(&mut headers, name, value);
+    }
+
+    if !credentials.cookie.is_empty() {
+        insert_header(&mut headers, "cookie", &credentials.cookie);
+    }
+    if !credentials.csrf_token.is_empty() {
+        insert_header(&mut headers, "x-csrftoken", &credentials.csrf_token);
+    }
+    return headers;
+}
+
+// Retry-After is either delta-seconds or an HTTP-date, clamped to `max` so
+// a server can't stall the run
+fn parse_retry_after(value: &str, now: DateTime<Utc>, max: Duration) -> Option<Duration> {
+    let value = value.trim();
+    let wait = match value.parse::<u64>() {
+        Ok(seconds) => Duration::from_secs(seconds),
+        Err(_) => {
+            let date = DateTime::parse_from_rfc2822(value).ok()?;
+            (date.with_timezone(&Utc) - now)
+                .to_std()
+                .unwrap_or(Duration::from_secs(0))
+        }
+    };
+    return Some(wait.min(max));
+}
+
+// exponential from `base` on the first retry, with equal jitter that keeps
+// at least half of the delay
+fn backoff(base: u64, max: u64, retry_idx: u32) -> Duration {
+    let exp = base
+        .saturating_mul(1u64 << retry_idx.saturating_sub(1).min(16))
+        .min(max);
+    let jitter = rand::thread_rng().gen_range(0..=exp);
+    return Duration::from_millis(exp / 2 + jitter / 2);
+}
+
+impl Requester {
+    pub fn new(config: &RequestConfig, credentials: &Credentials, verbose: bool) -> Requester {
+        let mut builder = reqwest::Client::builder()
+            .user_agent(config.user_agent.as_str())
+            .timeout(Duration::from_secs(config.timeout))
+            .default_headers(build_headers(config, credentials));
+
+        if !config.proxy.is_empty() {
+            match reqwest::Proxy::all(config.proxy.as_str()) {
+                Ok(proxy) => {
+                    if verbose {
+                        println!("[INFO] Using proxy={}", config.proxy);
+                    }
+                    builder = builder.proxy(proxy);
+                }
+                Err(err) => {
+                    println!(
+                        "[ERROR] Invalid proxy, ignored, proxy={}, e={}",
+                        config.proxy, err
+                    );
+                }
+            }
+        }
+        let client = match builder.build() {
+            Ok(client) => Ok(client),
+            Err(err) => {
+                println!("[ERROR] HTTP client cannot be built, e={}", err);
+                Err(err.to_string())
+            }
+        };
+
+        return Requester {
+            client,
+            limiter: RateLimiter::new(config.rate_limit, config.burst),
+            config: config.clone(),
+            verbose,
+        };
+    }
+
+    fn backoff(&self, retry_idx: u32) -> Duration {
+        return backoff(self.config.backoff_base, self.config.backoff_max, retry_idx);
+    }
+
+    async fn _send_one_request<T>(
+        &self,
+        client: &reqwest::Client,
+        url: &str,
+        payload: Option<&serde_json::Value>,
+    ) -> Result<T, RequestError>
+    where
+        T: DeserializeOwned,
+    {
+        self.limiter.acquire().await;
+
+        let req = match payload {
+            Some(payload) => client.post(url).json(payload),
+            None => client.get(url),
+        };
+        let resp = req.send().await?;
+        let status = resp.status();
+        if status == StatusCode::TOO_MANY_REQUESTS || status == StatusCode::SERVICE_UNAVAILABLE {
+            let retry_after = resp
+                .headers()
+                .get(header::RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| {
+                    parse_retry_after(
+                        value,
+                        Utc::now(),
+                        Duration::from_millis(self.config.backoff_max),
+                    )
+                });
+            return Err(RequestError::Throttled(retry_after));
+        }
+        if let Err(err) = resp.error_for_status_ref() {
+            if status.is_server_error() {
+                return Err(RequestError::Retryable(Box::new(err)));
+            }
+            return Err(RequestError::Fatal(Box::new(err)));
+        }
+
+        let body = resp.text().await?;
+        if self.config.fixture_mode == FixtureMode::Record {
+            write_fixture(&self.config.fixture_dir, url, payload, &body).await;
+        }
+        return serde_json::from_str::<T>(&body).map_err(|err| RequestError::Fatal(Box::new(err)));
     }
 
-    return Err(Box::new(io::Error::new(
-        io::ErrorKind::InvalidData,
-        "Error request after max retry count",
-    )));
+    pub async fn send_request<T>(&self, url: &str) -> Result<T, Box<dyn std::error::Error>>
+    where
+        T: DeserializeOwned,
+    {
+        return self.send(url, None).await;
+    }
+
+    // JSON POST, used for GraphQL endpoints
+    pub async fn send_post_request<T>(
+        &self,
+        url: &str,
+        payload: &serde_json::Value,
+    ) -> Result<T, Box<dyn std::error::Error>>
+    where
+        T: DeserializeOwned,
+    {
+        return self.send(url, Some(payload)).await;
+    }
+
+    async fn send<T>(
+        &self,
+        url: &str,
+        payload: Option<&serde_json::Value>,
+    ) -> Result<T, Box<dyn std::error::Error>>
+    where
+        T: DeserializeOwned,
+    {
+        if self.config.fixture_mode == FixtureMode::Replay {
+            if self.verbose {
+                println!("[INFO] Replay request url={}", url);
+            }
+            let body = read_fixture(&self.config.fixture_dir, url, payload).await?;
+            return Ok(serde_json::from_str::<T>(&body)?);
+        }
+
+        let client = match &self.client {
+            Ok(client) => client,
+            Err(err) => {
+                return Err(Box::new(io::Error::new(
+                    io::ErrorKind::Other,
+                    format!("HTTP client cannot be built, e={}", err),
+                )))
+            }
+        };
+
+        let max_retry = self.config.max_retry.max(1);
+        for retry_idx in 1u32..=max_retry {
+            let wait = match self._send_one_request::<T>(client, url, payload).await {
+                Ok(resp) => return Ok(resp),
+
+                Err(RequestError::Throttled(retry_after)) => {
+                    println!(
+                        "[WARN] Throttled when send_request, retry_idx=#{}, url={}",
+                        retry_idx, url
+                    );
+                    retry_after.unwrap_or_else(|| self.backoff(retry_idx))
+                }
+                Err(RequestError::Retryable(err)) => {
+                    println!(
+                        "[Error] When send_request, retry_idx=#{}, e={}",
+                        retry_idx, err
+                    );
+                    self.backoff(retry_idx)
+                }
+                Err(RequestError::Fatal(err)) => {
+                    println!("[ERROR] When send_request, not retried, url={}", url);
+                    return Err(err);
+                }
+            };
+
+            if retry_idx < max_retry {
+                if self.verbose {
+                    println!("[INFO] Retry after {}ms, url={}", wait.as_millis(), url);
+                }
+                tokio::time::sleep(wait).await;
+            }
+        }
+
+        return Err(Box::new(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "Error request after max retry count",
+        )));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn headers_carry_credentials() {
+        let mut config = RequestConfig::default();
+        config.headers.insert(
+            String::from("referer"),
+            String::from("https://leetcode.com"),
+        );
+        config
+            .headers
+            .insert(String::from("cookie"), String::from("from-config"));
+        config
+            .headers
+            .insert(String::from("bad header"), String::from("ignored"));
+        let credentials = Credentials {
+            cookie: String::from("LEETCODE_SESSION=abc"),
+            csrf_token: String::from("token"),
+        };
+
+        let headers = build_headers(&config, &credentials);
+        assert_eq!(headers["referer"], "https://leetcode.com");
+        // credentials win over a cookie header in the config
+        assert_eq!(headers["cookie"], "LEETCODE_SESSION=abc");
+        assert_eq!(headers["x-csrftoken"], "token");
+        assert_eq!(headers.len(), 3);
+
+        let headers = build_headers(&config, &Credentials::default());
+        assert_eq!(headers["cookie"], "from-config");
+        assert!(!headers.contains_key("x-csrftoken"));
+    }
+
+    #[test]
+    fn rate_limiter_allows_burst_then_waits() {
+        let limiter = RateLimiter::new(10, 3);
+        let now = Instant::now();
+        for _ in 0..3 {
+            assert_eq!(limiter.try_acquire(now), None);
+        }
+        let wait = limiter.try_acquire(now).unwrap();
+        assert!(wait > Duration::from_millis(50) && wait <= Duration::from_millis(100));
+    }
+
+    #[test]
+    fn rate_limiter_refills_up_to_burst() {
+        let limiter = RateLimiter::new(10, 3);
+        let now = Instant::now();
+        for _ in 0..3 {
+            limiter.try_acquire(now);
+        }
+
+        // 100ms at 10/s is one token
+        let later = now + Duration::from_millis(100);
+        assert_eq!(limiter.try_acquire(later), None);
+        assert!(limiter.try_acquire(later).is_some());
+
+        // a long pause never refills past the burst
+        let much_later = later + Duration::from_secs(60);
+        for _ in 0..3 {
+            assert_eq!(limiter.try_acquire(much_later), None);
+        }
+        assert!(limiter.try_acquire(much_later).is_some());
+    }
+
+    #[test]
+    fn backoff_starts_at_base() {
+        for _ in 0..100 {
+            let wait = backoff(500, 30000, 1);
+            assert!(wait >= Duration::from_millis(250) && wait <= Duration::from_millis(500));
+        }
+    }
+
+    #[test]
+    fn backoff_doubles_within_jitter_range() {
+        for retry_idx in 1..=5u32 {
+            let exp = 500u64 << (retry_idx - 1);
+            for _ in 0..100 {
+                let wait = backoff(500, 30000, retry_idx).as_millis() as u64;
+                assert!(wait >= exp / 2 && wait <= exp);
+            }
+        }
+    }
+
+    #[test]
+    fn backoff_is_capped_at_max() {
+        for retry_idx in [10u32, 16, 40, u32::MAX].iter() {
+            assert!(backoff(500, 30000, *retry_idx) <= Duration::from_millis(30000));
+        }
+    }
+
+    #[test]
+    fn retry_after_seconds() {
+        let now = Utc::now();
+        let max = Duration::from_secs(60);
+        assert_eq!(
+            parse_retry_after("5", now, max),
+            Some(Duration::from_secs(5))
+        );
+        assert_eq!(
+            parse_retry_after(" 0 ", now, max),
+            Some(Duration::from_secs(0))
+        );
+        assert_eq!(parse_retry_after("3600", now, max), Some(max));
+        assert_eq!(parse_retry_after("soon", now, max), None);
+    }
+
+    #[test]
+    fn retry_after_http_date() {
+        let now = Utc.ymd(2015, 10, 21).and_hms(7, 28, 0);
+        let max = Duration::from_secs(60);
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:30 GMT", now, max),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 09:00:00 GMT", now, max),
+            Some(max)
+        );
+        // a date in the past means retry right away
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:00:00 GMT", now, max),
+            Some(Duration::from_secs(0))
+        );
+    }
 }
@@ -1,19 +1,20 @@
 use crate::{
     model::{
-        config::{Config, Settings, WebsiteConfig},
-        render::{Submission, SubmissionStatus},
+        config::{Config, FixtureMode, Settings, WebsiteConfig},
+        render::{Attempt, Problem, Submission, SubmissionStatus, Theme},
         renderable::{Renderable, WebsiteTrait},
         website::{WebsiteContest, WebsiteUser},
     },
-    service::cache,
-    utils::{null, request},
+    service::{cache, secrets, theme::Glyph},
+    utils::{date::DateFormatter, finish_time, null, request},
 };
 use clap::ArgMatches;
 use futures::future;
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::{HashMap, HashSet},
-    sync::Arc,
+    collections::{BTreeSet, HashMap, HashSet},
+    sync::{Arc, Mutex},
 };
 
 #[derive(Serialize, Deserialize)]
@@ -22,6 +23,8 @@ struct SubmissionItem {
     date: i64,
     question_id: u32,
     submission_id: u32,
+    #[serde(default)]
+    lang: String,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -46,6 +49,8 @@ struct LeetcodeRankRequest {
 #[derive(Serialize, Deserialize)]
 struct LeetcodeContestInfo {
     start_time: i64,
+    #[serde(default)]
+    duration: i64,
     title: String,
 }
 
@@ -64,6 +69,136 @@ struct LeetcodeContestInfoRequest {
     questions: Vec<LeetcodeQuestionInfo>,
 }
 
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LeetcodeContestListItem {
+    title: String,
+    title_slug: String,
+    start_time: i64,
+    duration: i64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct LeetcodeContestListData {
+    #[serde(rename = "allContests")]
+    all_contests: Vec<LeetcodeContestListItem>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct LeetcodeContestListRequest {
+    data: LeetcodeContestListData,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct LeetcodeHistoryContest {
+    title: String,
+    start_time: i64,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct LeetcodeHistoryItem {
+    attended: bool,
+    rating: f64,
+    ranking: u32,
+    problems_solved: u32,
+    total_problems: u32,
+    finish_time_in_seconds: i64,
+    contest: LeetcodeHistoryContest,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LeetcodeHistoryData {
+    // null when the user doesn't exist
+    user_contest_ranking_history: Option<Vec<LeetcodeHistoryItem>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct LeetcodeHistoryRequest {
+    data: LeetcodeHistoryData,
+}
+
+#[derive(Serialize, Deserialize)]
+struct LeetcodeQuestionDifficulty {
+    difficulty: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct LeetcodeQuestionData {
+    question: Option<LeetcodeQuestionDifficulty>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct LeetcodeQuestionRequest {
+    data: LeetcodeQuestionData,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct LeetcodeRecentSubmission {
+    title_slug: String,
+    // unix seconds, sent as a string
+    timestamp: String,
+    status_display: String,
+    lang: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LeetcodeRecentSubmissionData {
+    recent_submission_list: Option<Vec<LeetcodeRecentSubmission>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct LeetcodeRecentSubmissionRequest {
+    data: LeetcodeRecentSubmissionData,
+}
+
+type Participation = HashMap<String, HashMap<String, bool>>;
+
+// what searching a contest for the watched users leaves behind, used to pick
+// the global top and the neighbours afterwards
+struct RankSearch<'a> {
+    contest_type: &'a str,
+    contest_id: u32,
+    contest_info: &'a LeetcodeContestInfo,
+    questions: &'a [LeetcodeQuestionInfo],
+    problems: &'a [Problem],
+    // ranking pages already fetched, by page number
+    pages: HashMap<u32, LeetcodeRankRequest>,
+    // 0-based global positions of the watched users
+    friend_positions: Vec<usize>,
+    friends: &'a [String],
+}
+
+const GRAPHQL_URL: &str = "https://leetcode.com/graphql";
+const QUESTION_QUERY: &str =
+    "query questionData($titleSlug: String!) { question(titleSlug: $titleSlug) { difficulty } }";
+const RECENT_SUBMISSION_QUERY: &str = "query recentSubmissionList($username: String!, $limit: Int) { recentSubmissionList(username: $username, limit: $limit) { titleSlug timestamp statusDisplay lang } }";
+// the public list only keeps this many latest submissions
+const RECENT_SUBMISSION_LIMIT: u32 = 20;
+// entries per ranking page
+const RANK_PAGE_SIZE: u32 = 25;
+const HISTORY_QUERY: &str = "query userContestRankingHistory($username: String!) { userContestRankingHistory(username: $username) { attended rating ranking problemsSolved totalProblems finishTimeInSeconds contest { title startTime } } }";
+
+// weekly-contest-227 => w227, biweekly-contest-45 => b45
+fn contest_slug_to_id(title_slug: &str) -> Option<String> {
+    let (contest_type, number) = if let Some(number) = title_slug.strip_prefix("weekly-contest-") {
+        ("w", number)
+    } else if let Some(number) = title_slug.strip_prefix("biweekly-contest-") {
+        ("b", number)
+    } else {
+        return None;
+    };
+
+    return number
+        .parse::<u32>()
+        .ok()
+        .map(|number| format!("{}{}", contest_type, number));
+}
+
 pub struct LeetcodeWeb {
     pub verbose: bool,
     pub config: WebsiteConfig,
@@ -71,6 +206,12 @@ pub struct LeetcodeWeb {
     pub enable_cache: bool,
     pub is_live: bool,
     pub runtime: Arc<tokio::runtime::Runtime>,
+    pub requester: request::Requester,
+
+    // kept for the whole run whatever `cache` says, live mode renders over
+    // and over and history is read by several subcommands
+    history_memo: Mutex<HashMap<String, Vec<LeetcodeHistoryItem>>>,
+    difficulty_memo: Mutex<HashMap<String, String>>,
 }
 
 impl LeetcodeWeb {
@@ -92,7 +233,10 @@ impl LeetcodeWeb {
             id = contest_id,
             contest_type = contest_type_full
         );
-        let res = request::send_request::<LeetcodeContestInfoRequest>(&url).await?;
+        let res = self
+            .requester
+            .send_request::<LeetcodeContestInfoRequest>(&url)
+            .await?;
         return Ok(res);
     }
 
@@ -127,18 +271,504 @@ impl LeetcodeWeb {
             }
         }
 
-        let res = request::send_request::<LeetcodeRankRequest>(&url).await?;
+        let res = self
+            .requester
+            .send_request::<LeetcodeRankRequest>(&url)
+            .await?;
         if self.enable_cache && res.is_past {
             cache::set_cache(&cache_key, &res).await;
         }
         return Ok(res);
     }
 
+    async fn send_contest_list_request(
+        &self,
+    ) -> Result<Vec<LeetcodeContestListItem>, Box<dyn std::error::Error>> {
+        let payload = serde_json::json!({
+            "query": "{ allContests { title titleSlug startTime duration } }",
+            "variables": {},
+        });
+        let res = self
+            .requester
+            .send_post_request::<LeetcodeContestListRequest>(GRAPHQL_URL, &payload)
+            .await?;
+
+        let mut contests: Vec<LeetcodeContestListItem> = res
+            .data
+            .all_contests
+            .into_iter()
+            .filter(|contest| contest_slug_to_id(&contest.title_slug).is_some())
+            .collect();
+        contests.sort_by(|lhs, rhs| rhs.start_time.cmp(&lhs.start_time));
+        return Ok(contests);
+    }
+
+    // newest first, weekly and biweekly contests only
+    fn list_contests(&self) -> Vec<LeetcodeContestListItem> {
+        match self.runtime.block_on(self.send_contest_list_request()) {
+            Ok(contests) => return contests,
+            Err(err) => {
+                println!("[ERROR] When fetching contest list, e={}", err);
+                return vec![];
+            }
+        }
+    }
+
+    // ids of the latest `count` contests that have already started, oldest first
+    fn recent_contest_ids(&self, count: usize, date: &DateFormatter) -> Vec<String> {
+        let now = date.timestamp();
+        let mut contest_ids: Vec<String> = self
+            .list_contests()
+            .iter()
+            .filter(|contest| contest.start_time <= now)
+            .take(count)
+            .filter_map(|contest| contest_slug_to_id(&contest.title_slug))
+            .collect();
+        contest_ids.reverse();
+        return contest_ids;
+    }
+
+    fn print_contest_list(&self, count: usize, date: &DateFormatter, theme: Theme) {
+        let now = date.timestamp();
+        let contests = self.list_contests();
+        let (upcoming, past): (Vec<_>, Vec<_>) = contests
+            .iter()
+            .partition(|contest| contest.start_time > now);
+
+        let print_contest = |contest: &LeetcodeContestListItem| {
+            println!(
+                "  {:<6} {:<32} {} {}{}",
+                contest_slug_to_id(&contest.title_slug).unwrap(),
+                contest.title,
+                date.format(contest.start_time),
+                theme.glyph(Glyph::Duration),
+                finish_time::seconds_to_finish_time(contest.duration)
+            );
+        };
+
+        println!("{}", theme.glyph(Glyph::Upcoming));
+        for contest in upcoming.iter().rev() {
+            print_contest(contest);
+        }
+        println!("{}", theme.glyph(Glyph::Recent));
+        for contest in past.iter().take(count) {
+            print_contest(contest);
+        }
+    }
+
+    // every rated contest since the user registered, oldest first
+    async fn send_user_history_request(
+        &self,
+        username: &str,
+    ) -> Result<Vec<LeetcodeHistoryItem>, Box<dyn std::error::Error>> {
+        if let Some(memo) = self.history_memo.lock().unwrap().get(username) {
+            return Ok(memo.clone());
+        }
+
+        let payload = serde_json::json!({
+            "query": HISTORY_QUERY,
+            "variables": { "username": username },
+        });
+        let res = self
+            .requester
+            .send_post_request::<LeetcodeHistoryRequest>(GRAPHQL_URL, &payload)
+            .await?;
+
+        match res.data.user_contest_ranking_history {
+            Some(history) => {
+                self.history_memo
+                    .lock()
+                    .unwrap()
+                    .insert(username.to_string(), history.clone());
+                return Ok(history);
+            }
+            None => {
+                return Err(Box::new(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    format!("no contest history for user={}", username),
+                )));
+            }
+        }
+    }
+
+    async fn send_question_difficulty_request(
+        &self,
+        title_slug: &str,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        if let Some(memo) = self.difficulty_memo.lock().unwrap().get(title_slug) {
+            return Ok(memo.clone());
+        }
+        let cache_key = format!("lc_q_{}", title_slug);
+        if self.enable_cache {
+            if let Some(memo) = cache::get_cache::<String>(&cache_key).await {
+                self.difficulty_memo
+                    .lock()
+                    .unwrap()
+                    .insert(title_slug.to_string(), memo.clone());
+                return Ok(memo);
+            }
+        }
+
+        let payload = serde_json::json!({
+            "query": QUESTION_QUERY,
+            "variables": { "titleSlug": title_slug },
+        });
+        let res = self
+            .requester
+            .send_post_request::<LeetcodeQuestionRequest>(GRAPHQL_URL, &payload)
+            .await?;
+
+        let difficulty = res
+            .data
+            .question
+            .map(|question| question.difficulty)
+            .unwrap_or_default();
+        if !difficulty.is_empty() {
+            self.difficulty_memo
+                .lock()
+                .unwrap()
+                .insert(title_slug.to_string(), difficulty.clone());
+        }
+        if self.enable_cache && !difficulty.is_empty() {
+            cache::set_cache(&cache_key, &difficulty).await;
+        }
+        return Ok(difficulty);
+    }
+
+    async fn request_problems(&self, questions: &[LeetcodeQuestionInfo]) -> Vec<Problem> {
+        let mut difficulty_futures = vec![];
+        for question in questions.iter() {
+            difficulty_futures.push(self.send_question_difficulty_request(&question.title_slug));
+        }
+
+        let difficulties = future::join_all(difficulty_futures).await;
+        let mut problems = vec![];
+        for (question, difficulty) in questions.iter().zip(difficulties.into_iter()) {
+            let difficulty = match difficulty {
+                Ok(difficulty) => difficulty,
+                Err(err) => {
+                    println!(
+                        "[WARN] When fetching difficulty, slug={}, e={}",
+                        question.title_slug, err
+                    );
+                    String::from("")
+                }
+            };
+
+            problems.push(Problem {
+                title: question.title.clone(),
+                url: format!("https://leetcode.com/problems/{}/", question.title_slug),
+                difficulty,
+                credit: question.credit,
+            });
+        }
+        return problems;
+    }
+
+    // submissions of the user made during the contest, oldest first; only
+    // recent contests can be covered as the public list is short, older
+    // ones are an error instead of an empty list
+    async fn send_attempts_request(
+        &self,
+        contest_type: &str,
+        contest_id: u32,
+        username: &str,
+        contest_info: &LeetcodeContestInfo,
+        is_past: bool,
+    ) -> Result<Vec<LeetcodeRecentSubmission>, Box<dyn std::error::Error>> {
+        let cache_key = format!("lc_{}{}_attempts_{}", contest_type, contest_id, username);
+        if self.enable_cache {
+            if let Some(memo) = cache::get_cache::<Vec<LeetcodeRecentSubmission>>(&cache_key).await
+            {
+                return Ok(memo);
+            }
+        }
+
+        let payload = serde_json::json!({
+            "query": RECENT_SUBMISSION_QUERY,
+            "variables": { "username": username, "limit": RECENT_SUBMISSION_LIMIT },
+        });
+        let res = self
+            .requester
+            .send_post_request::<LeetcodeRecentSubmissionRequest>(GRAPHQL_URL, &payload)
+            .await?;
+
+        let duration = if contest_info.duration > 0 {
+            contest_info.duration
+        } else {
+            5400
+        };
+        let start_time = contest_info.start_time;
+        let recent = res.data.recent_submission_list.unwrap_or_default();
+
+        // a full list only reaches back to its oldest entry, anything before
+        // that is cut off and would look like a contest without attempts
+        let oldest = recent
+            .iter()
+            .filter_map(|attempt| attempt.timestamp.parse::<i64>().ok())
+            .min();
+        let mut is_partial = false;
+        if recent.len() as u32 >= RECENT_SUBMISSION_LIMIT {
+            if let Some(oldest) = oldest {
+                if oldest > start_time + duration {
+                    return Err(format!(
+                        "contest is older than the last {} submissions, attempts unavailable",
+                        RECENT_SUBMISSION_LIMIT
+                    )
+                    .into());
+                }
+                if oldest > start_time {
+                    is_partial = true;
+                    println!(
+                        "[WARN] Attempts may be incomplete, only the last {} submissions are public, user={}, contest={}{}",
+                        RECENT_SUBMISSION_LIMIT, username, contest_type, contest_id
+                    );
+                }
+            }
+        }
+
+        let mut attempts: Vec<LeetcodeRecentSubmission> = recent
+            .into_iter()
+            .filter(|attempt| match attempt.timestamp.parse::<i64>() {
+                Ok(timestamp) => timestamp >= start_time && timestamp <= start_time + duration,
+                Err(_) => false,
+            })
+            .collect();
+        attempts.sort_by_key(|attempt| attempt.timestamp.parse::<i64>().unwrap());
+
+        // an empty list may just mean the contest fell out of the recent list
+        if self.enable_cache && is_past && !is_partial && !attempts.is_empty() {
+            cache::set_cache(&cache_key, &attempts).await;
+        }
+        return Ok(attempts);
+    }
+
+    async fn fill_attempts(
+        &self,
+        contest_type: &str,
+        contest_id: u32,
+        contest_info: &LeetcodeContestInfo,
+        questions: &[LeetcodeQuestionInfo],
+        is_past: bool,
+        players: &mut Vec<WebsiteUser>,
+    ) {
+        let mut attempt_futures = vec![];
+        for player in players.iter() {
+            attempt_futures.push(self.send_attempts_request(
+                contest_type,
+                contest_id,
+                &player.username,
+                contest_info,
+                is_past,
+            ));
+        }
+        let attempt_results = future::join_all(attempt_futures).await;
+
+        for (player, attempt_result) in players.iter_mut().zip(attempt_results.into_iter()) {
+            let attempts = match attempt_result {
+                Ok(attempts) => attempts,
+                Err(err) => {
+                    println!(
+                        "[WARN] When fetching attempts, user={}, e={}",
+                        player.username, err
+                    );
+                    continue;
+                }
+            };
+
+            for (submission, question) in player.submissions.iter_mut().zip(questions.iter()) {
+                for attempt in attempts.iter() {
+                    if attempt.title_slug != question.title_slug {
+                        continue;
+                    }
+
+                    if submission.language.is_empty() && attempt.status_display == "Accepted" {
+                        submission.language = attempt.lang.clone();
+                    }
+                    submission.attempts.push(Attempt {
+                        time: attempt.timestamp.parse::<i64>().unwrap() - contest_info.start_time,
+                        verdict: attempt.status_display.clone(),
+                        language: attempt.lang.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    // username => contest title => attended, users whose history can't be
+    // fetched are left out and will be searched in the ranking pages as usual
+    async fn fetch_participation(&self, users: &[String]) -> Participation {
+        let mut history_futures = vec![];
+        for username in users.iter() {
+            history_futures.push(self.send_user_history_request(username));
+        }
+
+        let histories = future::join_all(history_futures).await;
+        let mut participation = Participation::new();
+        for (username, history_result) in users.iter().zip(histories.into_iter()) {
+            match history_result {
+                Ok(history) => {
+                    let contests = history
+                        .into_iter()
+                        .map(|item| (item.contest.title, item.attended))
+                        .collect();
+                    participation.insert(username.clone(), contests);
+                }
+                Err(err) => {
+                    if self.verbose {
+                        println!("[INFO] Participation unknown, user={}, e={}", username, err);
+                    }
+                }
+            }
+        }
+        return participation;
+    }
+
+    // a ranking entry with its submissions lined up with the questions
+    fn build_player(
+        &self,
+        rank: &RankItem,
+        submission_hashmap: &HashMap<String, SubmissionItem>,
+        contest_info: &LeetcodeContestInfo,
+        questions: &[LeetcodeQuestionInfo],
+        problems: &[Problem],
+    ) -> WebsiteUser {
+        let mut submissions_vec = Vec::<Submission>::new();
+
+        for question_index in 0..questions.len() {
+            let question = &questions[question_index];
+            let question_id = question.question_id;
+            let question_id_str = question_id.to_string();
+
+            match submission_hashmap.get(&question_id_str) {
+                None => {
+                    let submission_status;
+                    if self.is_live {
+                        submission_status = SubmissionStatus::Pending;
+                    } else {
+                        submission_status = SubmissionStatus::Unaccepted;
+                    }
+
+                    submissions_vec.push(Submission {
+                        fail_count: 0,
+                        finish_time: 0,
+                        status: submission_status,
+                        score: 0,
+                        title: format!("T{}", question_index + 1),
+                        problem: problems[question_index].clone(),
+                        language: String::from(""),
+                        attempts: vec![],
+                    });
+                }
+                Some(submission) => {
+                    submissions_vec.push(Submission {
+                        fail_count: submission.fail_count,
+                        finish_time: submission.date - contest_info.start_time,
+                        status: SubmissionStatus::Accepted,
+                        score: question.credit,
+                        title: format!("T{}", question_index + 1),
+                        problem: problems[question_index].clone(),
+                        language: submission.lang.clone(),
+                        attempts: vec![],
+                    });
+                }
+            }
+        }
+
+        return WebsiteUser {
+            username: rank.username.clone(),
+            country: rank.country_name.clone(),
+            finish_time: rank.finish_time - contest_info.start_time,
+            global_rank: rank.rank,
+            score: rank.score,
+            rating: None,
+            problems_solved: submissions_vec
+                .iter()
+                .filter(|submission| submission.status == SubmissionStatus::Accepted)
+                .count() as u32,
+            total_problems: questions.len() as u32,
+            attended: true,
+            is_friend: true,
+            submissions: submissions_vec,
+        };
+    }
+
+    // the global top and the neighbours of the watched users, taken from the
+    // pages already searched plus any page they spill over onto
+    async fn non_friend_players(&self, search: RankSearch<'_>) -> Vec<WebsiteUser> {
+        let RankSearch {
+            contest_type,
+            contest_id,
+            contest_info,
+            questions,
+            problems,
+            mut pages,
+            friend_positions,
+            friends,
+        } = search;
+        let neighbours = self.config.neighbours as usize;
+        let mut positions = BTreeSet::<usize>::new();
+        positions.extend(0..self.config.top_global as usize);
+        for position in friend_positions.iter() {
+            positions.extend(position.saturating_sub(neighbours)..=position + neighbours);
+        }
+
+        let page_size = RANK_PAGE_SIZE as usize;
+        let missing_pages: BTreeSet<u32> = positions
+            .iter()
+            .map(|position| (position / page_size) as u32 + 1)
+            .filter(|page| !pages.contains_key(page))
+            .collect();
+        let mut rank_futures = vec![];
+        for page in missing_pages.iter() {
+            if self.verbose {
+                println!("[INFO] ({}), current page={}", contest_info.title, page);
+            }
+            rank_futures.push(self.send_contest_rank_request(contest_type, contest_id, *page));
+        }
+        let ranks = future::join_all(rank_futures).await;
+        for (page, rank_result) in missing_pages.into_iter().zip(ranks.into_iter()) {
+            match rank_result {
+                Ok(rank) => {
+                    pages.insert(page, rank);
+                }
+                Err(err) => {
+                    println!("[Error] When fetching rank result, e={}", err);
+                }
+            }
+        }
+
+        let mut players = vec![];
+        for position in positions.into_iter() {
+            let rank = match pages.get(&((position / page_size) as u32 + 1)) {
+                Some(rank) => rank,
+                None => continue,
+            };
+            let index = position % page_size;
+            if index >= rank.total_rank.len() || friends.contains(&rank.total_rank[index].username)
+            {
+                continue;
+            }
+
+            let mut player = self.build_player(
+                &rank.total_rank[index],
+                &rank.submissions[index],
+                contest_info,
+                questions,
+                problems,
+            );
+            player.is_friend = false;
+            players.push(player);
+        }
+        return players;
+    }
+
     async fn request_leetcode(
         &self,
         contest_type: String,
         contest_id: u32,
         players: Vec<String>,
+        participation: &Participation,
     ) -> Result<WebsiteContest, Box<dyn std::error::Error>> {
         let contest_req = self
             .send_contest_info_request(&contest_type, contest_id)
@@ -146,15 +776,40 @@ impl LeetcodeWeb {
 
         let contest_info = contest_req.contest;
         let questions = contest_req.questions;
+        let problems = self.request_problems(&questions).await;
 
         let mut searching_players = HashSet::<String>::new();
+        let mut absent_players = Vec::<String>::new();
         for player in players.iter() {
-            searching_players.insert(player.clone());
+            let attended = participation
+                .get(player)
+                .and_then(|contests| contests.get(&contest_info.title));
+
+            // a missing entry means the rating isn't settled yet, still search
+            if attended == Some(&false) {
+                if self.verbose {
+                    println!(
+                        "[INFO] ({}), skip absent user={}",
+                        contest_info.title, player
+                    );
+                }
+                absent_players.push(player.clone());
+            } else {
+                searching_players.insert(player.clone());
+            }
         }
 
         let mut website_players = Vec::<WebsiteUser>::new();
+        let mut is_past = false;
+        let mut participant_count = 0u64;
+        // pages and where the watched users were found, kept to pick
+        // non-friends from afterwards
+        let keep_pages = self.config.top_global > 0 || self.config.neighbours > 0;
+        let mut pages = HashMap::<u32, LeetcodeRankRequest>::new();
+        let mut friend_positions = Vec::<usize>::new();
         let mut page = 1u32;
-        while !searching_players.is_empty() && (page - 1u32) * 25u32 < self.config.max_rank {
+        while !searching_players.is_empty() && (page - 1u32) * RANK_PAGE_SIZE < self.config.max_rank
+        {
             let mut ranks = vec![];
             for page_offset in 0u32..self.config.concurrent {
                 if self.verbose {
@@ -173,10 +828,13 @@ impl LeetcodeWeb {
             let ranks = future::join_all(ranks).await;
 
             let mut zero_player_page_cnt = 0;
-            for rank_result in ranks.iter() {
+            for (page_offset, rank_result) in ranks.into_iter().enumerate() {
+                let rank_page = page + page_offset as u32;
                 match rank_result {
                     Ok(rank) => {
                         assert_eq!(rank.submissions.len(), rank.total_rank.len());
+                        is_past = is_past || rank.is_past;
+                        participant_count = participant_count.max(rank.user_num);
 
                         let playeres_in_page = rank.submissions.len();
                         if playeres_in_page == 0 {
@@ -191,50 +849,18 @@ impl LeetcodeWeb {
                             }
 
                             searching_players.remove(&rank.username);
-                            let mut submissions_vec = Vec::<Submission>::new();
-
-                            for question_index in 0..questions.len() {
-                                let question = &questions[question_index];
-                                let question_id = question.question_id;
-                                let question_id_str = question_id.to_string();
-
-                                match submission_hashmap.get(&question_id_str) {
-                                    None => {
-                                        let submission_status;
-                                        if self.is_live {
-                                            submission_status = SubmissionStatus::Pending;
-                                        } else {
-                                            submission_status = SubmissionStatus::Unaccepted;
-                                        }
-
-                                        submissions_vec.push(Submission {
-                                            fail_count: 0,
-                                            finish_time: 0,
-                                            status: submission_status,
-                                            score: 0,
-                                            title: format!("T{}", question_index + 1),
-                                        });
-                                    }
-                                    Some(submission) => {
-                                        submissions_vec.push(Submission {
-                                            fail_count: submission.fail_count,
-                                            finish_time: submission.date - contest_info.start_time,
-                                            status: SubmissionStatus::Accepted,
-                                            score: question.credit,
-                                            title: format!("T{}", question_index + 1),
-                                        });
-                                    }
-                                }
-                            }
+                            friend_positions.push(((rank_page - 1) * RANK_PAGE_SIZE) as usize + i);
+                            website_players.push(self.build_player(
+                                rank,
+                                submission_hashmap,
+                                &contest_info,
+                                &questions,
+                                &problems,
+                            ));
+                        }
 
-                            website_players.push(WebsiteUser {
-                                username: rank.username.clone(),
-                                country: rank.country_name.clone(),
-                                finish_time: rank.finish_time - contest_info.start_time,
-                                global_rank: rank.rank,
-                                score: rank.score,
-                                submissions: submissions_vec,
-                            });
+                        if keep_pages {
+                            pages.insert(rank_page, rank);
                         }
                     }
                     Err(err) => {
@@ -249,9 +875,147 @@ impl LeetcodeWeb {
             page += self.config.concurrent;
         }
 
+        if self.config.fetch_attempts {
+            self.fill_attempts(
+                &contest_type,
+                contest_id,
+                &contest_info,
+                &questions,
+                is_past,
+                &mut website_players,
+            )
+            .await;
+        }
+
+        if keep_pages {
+            let mut non_friends = self
+                .non_friend_players(RankSearch {
+                    contest_type: &contest_type,
+                    contest_id,
+                    contest_info: &contest_info,
+                    questions: &questions,
+                    problems: &problems,
+                    pages,
+                    friend_positions,
+                    friends: &players,
+                })
+                .await;
+            website_players.append(&mut non_friends);
+        }
+
+        for username in absent_players.into_iter() {
+            website_players.push(WebsiteUser {
+                username,
+                country: String::from(""),
+                finish_time: 0,
+                global_rank: 0,
+                score: 0,
+                rating: None,
+                problems_solved: 0,
+                total_problems: questions.len() as u32,
+                attended: false,
+                is_friend: true,
+                submissions: vec![],
+            });
+        }
+
         return Ok(WebsiteContest {
+            id: format!("{}{}", contest_type, contest_id),
             name: String::from("LeetCode ") + &contest_info.title,
             date: contest_info.start_time,
+            participant_count,
+            players: website_players,
+        });
+    }
+
+    // walk the ranking pages in order until `top` participants from the
+    // country are found, watched or not
+    async fn request_country(
+        &self,
+        contest_type: String,
+        contest_id: u32,
+        country: &str,
+        top: usize,
+    ) -> Result<WebsiteContest, Box<dyn std::error::Error>> {
+        let contest_req = self
+            .send_contest_info_request(&contest_type, contest_id)
+            .await?;
+
+        let contest_info = contest_req.contest;
+        let questions = contest_req.questions;
+        let problems = self.request_problems(&questions).await;
+
+        let mut website_players = Vec::<WebsiteUser>::new();
+        let mut participant_count = 0u64;
+        let mut page = 1u32;
+        while website_players.len() < top && (page - 1u32) * RANK_PAGE_SIZE < self.config.max_rank {
+            let mut ranks = vec![];
+            for page_offset in 0u32..self.config.concurrent {
+                if self.verbose {
+                    println!(
+                        "[INFO] ({}), current page={}",
+                        contest_info.title,
+                        page + page_offset
+                    );
+                }
+                ranks.push(self.send_contest_rank_request(
+                    &contest_type,
+                    contest_id,
+                    page + page_offset,
+                ))
+            }
+            let ranks = future::join_all(ranks).await;
+
+            let mut zero_player_page_cnt = 0;
+            for rank_result in ranks.iter() {
+                match rank_result {
+                    Ok(rank) => {
+                        participant_count = participant_count.max(rank.user_num);
+                        if rank.total_rank.is_empty() {
+                            zero_player_page_cnt += 1;
+                        }
+
+                        for (submission_hashmap, rank) in
+                            rank.submissions.iter().zip(rank.total_rank.iter())
+                        {
+                            if rank.country_name.to_lowercase() == country.to_lowercase() {
+                                website_players.push(self.build_player(
+                                    rank,
+                                    submission_hashmap,
+                                    &contest_info,
+                                    &questions,
+                                    &problems,
+                                ));
+                            }
+                        }
+                    }
+                    Err(err) => {
+                        println!("[Error] When fetching rank result, e={}", err);
+                    }
+                }
+            }
+
+            if zero_player_page_cnt == self.config.concurrent {
+                if self.verbose {
+                    println!("[INFO] Exit searching, already hit the end of page");
+                }
+                break;
+            }
+            page += self.config.concurrent;
+        }
+
+        // pages come back in order, so these are the best of the country
+        website_players.truncate(top);
+        // spelled the way the ranking pages spell it
+        let country = match website_players.first() {
+            Some(player) => player.country.clone(),
+            None => country.to_string(),
+        };
+        return Ok(WebsiteContest {
+            id: format!("{}{}", contest_type, contest_id),
+            name: format!("LeetCode {} ({})", contest_info.title, country),
+            date: contest_info.start_time,
+            participant_count,
             players: website_players,
         });
     }
@@ -259,6 +1023,12 @@ impl LeetcodeWeb {
     async fn __render(&self, contests: &Vec<String>, users: &Vec<String>) -> Vec<WebsiteContest> {
         let verbose = false;
 
+        let participation = if !self.is_live && self.config.check_participation {
+            self.fetch_participation(users).await
+        } else {
+            Participation::new()
+        };
+
         let mut web_contests = Vec::<WebsiteContest>::new();
         let mut contest_futures = vec![];
 
@@ -282,6 +1052,7 @@ impl LeetcodeWeb {
                     contest_type,
                     contest_number,
                     users.to_vec(),
+                    &participation,
                 ));
             } else {
                 panic!("contest_id={} invalid", contest_id)
@@ -302,6 +1073,69 @@ impl LeetcodeWeb {
         return web_contests;
     }
 
+    async fn __render_history(&self, users: &[String], last: usize) -> Vec<WebsiteContest> {
+        let mut history_futures = vec![];
+        for username in users.iter() {
+            if self.verbose {
+                println!("[INFO] Fetching contest history, user={}", username);
+            }
+            history_futures.push(self.send_user_history_request(username));
+        }
+
+        let histories = future::join_all(history_futures).await;
+        let mut contests = IndexMap::<String, WebsiteContest>::new();
+        for (username, history_result) in users.iter().zip(histories.into_iter()) {
+            let history = match history_result {
+                Ok(history) => history,
+                Err(err) => {
+                    println!("[ERROR] When fetching user history, e={}", err);
+                    continue;
+                }
+            };
+
+            let history: Vec<LeetcodeHistoryItem> =
+                history.into_iter().filter(|item| item.attended).collect();
+            let skip = if last > 0 && history.len() > last {
+                history.len() - last
+            } else {
+                0
+            };
+            for item in history.into_iter().skip(skip) {
+                let contest = contests
+                    .entry(item.contest.title.clone())
+                    .or_insert_with(|| WebsiteContest {
+                        id: contest_slug_to_id(
+                            &item.contest.title.to_lowercase().replace(' ', "-"),
+                        )
+                        .unwrap_or_default(),
+                        name: String::from("LeetCode ") + &item.contest.title,
+                        date: item.contest.start_time,
+                        participant_count: 0,
+                        players: vec![],
+                    });
+
+                contest.players.push(WebsiteUser {
+                    username: username.clone(),
+                    country: String::from(""),
+                    finish_time: item.finish_time_in_seconds,
+                    global_rank: item.ranking,
+                    score: 0,
+                    rating: Some(item.rating),
+                    problems_solved: item.problems_solved,
+                    total_problems: item.total_problems,
+                    attended: true,
+                    is_friend: true,
+                    submissions: vec![],
+                });
+            }
+        }
+
+        let mut web_contests: Vec<WebsiteContest> =
+            contests.into_iter().map(|(_, contest)| contest).collect();
+        web_contests.sort_by(|lhs, rhs| lhs.date.cmp(&rhs.date));
+        return web_contests;
+    }
+
     fn render_live(&self) -> Vec<WebsiteContest> {
         let contests = &self.config.live_contests;
         let users = &self.config.live_users;
@@ -317,29 +1151,53 @@ impl LeetcodeWeb {
     }
 }
 
-impl Renderable for LeetcodeWeb {
-    fn new(
+impl LeetcodeWeb {
+    fn create(
         verbose: bool,
         config: Config,
         runtime: Arc<tokio::runtime::Runtime>,
         is_live: bool,
-    ) -> Box<dyn Renderable> {
+    ) -> LeetcodeWeb {
         let mut instance = LeetcodeWeb {
             verbose,
+            requester: request::Requester::new(
+                &config.leetcode.request,
+                &secrets::read_credentials(
+                    LeetcodeWeb::website_name(),
+                    &config.leetcode.request.secrets_file,
+                    &config.leetcode.request.config_dir,
+                ),
+                verbose,
+            ),
             config: config.leetcode,
             runtime,
 
             enable_cache: false,
             is_live,
+            history_memo: Mutex::new(HashMap::new()),
+            difficulty_memo: Mutex::new(HashMap::new()),
         };
 
-        if is_live {
+        // fixtures have to see every response, and replay must not touch
+        // ./cache either
+        if is_live || instance.config.request.fixture_mode != FixtureMode::Off {
             instance.enable_cache = false;
         } else {
             instance.enable_cache = instance.config.cache;
         }
 
-        return Box::new(instance);
+        return instance;
+    }
+}
+
+impl Renderable for LeetcodeWeb {
+    fn new(
+        verbose: bool,
+        config: Config,
+        runtime: Arc<tokio::runtime::Runtime>,
+        is_live: bool,
+    ) -> Box<dyn Renderable> {
+        return Box::new(LeetcodeWeb::create(verbose, config, runtime, is_live));
     }
 
     fn render(&self) -> Vec<WebsiteContest> {
@@ -349,6 +1207,42 @@ impl Renderable for LeetcodeWeb {
             return self.render_contest();
         }
     }
+
+    fn render_history(&self, last: usize) -> Vec<WebsiteContest> {
+        return self
+            .runtime
+            .block_on(self.__render_history(&self.config.users, last));
+    }
+
+    fn render_country(
+        &self,
+        contest_id: &str,
+        country: &str,
+        top: usize,
+    ) -> Option<WebsiteContest> {
+        let contest_number = match contest_id.get(1..).map(|number| number.parse::<u32>()) {
+            Some(Ok(number)) if contest_id.starts_with("w") || contest_id.starts_with("b") => {
+                number
+            }
+            _ => {
+                println!("[ERROR] Invalid contest_id={}", contest_id);
+                return None;
+            }
+        };
+
+        match self.runtime.block_on(self.request_country(
+            (&contest_id[0..1]).to_string(),
+            contest_number,
+            country,
+            top,
+        )) {
+            Ok(contest) => return Some(contest),
+            Err(err) => {
+                println!("[ERROR] When fetching contest {}", err);
+                return None;
+            }
+        }
+    }
 }
 
 impl WebsiteTrait for LeetcodeWeb {
@@ -356,7 +1250,11 @@ impl WebsiteTrait for LeetcodeWeb {
         return "leetcode";
     }
 
-    fn subcommand_match(website_matches: &ArgMatches, settings: &mut Settings) -> bool {
+    fn subcommand_match(
+        website_matches: &ArgMatches,
+        settings: &mut Settings,
+        runtime: Arc<tokio::runtime::Runtime>,
+    ) -> bool {
         match website_matches.subcommand() {
             ("user", Some(arg_matches)) => match arg_matches.subcommand() {
                 ("add", Some(arg_matches)) => {
@@ -411,7 +1309,33 @@ impl WebsiteTrait for LeetcodeWeb {
             },
             ("contest", Some(arg_matches)) => match arg_matches.subcommand() {
                 ("add", Some(arg_matches)) => {
-                    let contest_id = arg_matches.value_of("contest_id").unwrap();
+                    let contest_ids: Vec<String>;
+                    if arg_matches.is_present("latest") || arg_matches.is_present("last") {
+                        let count = match arg_matches.value_of("last") {
+                            Some(last) => match last.parse::<usize>() {
+                                Ok(count) => count,
+                                Err(_) => {
+                                    println!("[INFO] ❌ Invalid contest count {}", last);
+                                    return false;
+                                }
+                            },
+                            None => 1,
+                        };
+
+                        let web = LeetcodeWeb::create(
+                            settings.verbose,
+                            settings.config.clone(),
+                            runtime,
+                            false,
+                        );
+                        contest_ids = web.recent_contest_ids(count, &settings.date);
+                        if contest_ids.is_empty() {
+                            println!("[INFO] ❌ No recent contest found");
+                            return false;
+                        }
+                    } else {
+                        contest_ids = vec![arg_matches.value_of("contest_id").unwrap().to_string()];
+                    }
 
                     let vec;
                     if arg_matches.is_present("live") {
@@ -420,12 +1344,21 @@ impl WebsiteTrait for LeetcodeWeb {
                         vec = &mut settings.config.leetcode.contests;
                     }
 
-                    vec.push(contest_id.to_string());
-                    println!(
-                        "[INFO] 🔧 Added contest_id {} to LeetCode config",
-                        contest_id
-                    );
-                    return true;
+                    let mut changed = false;
+                    for contest_id in contest_ids.into_iter() {
+                        if vec.contains(&contest_id) {
+                            println!("[INFO] ❌ Contest {} already exists", contest_id);
+                            continue;
+                        }
+
+                        println!(
+                            "[INFO] 🔧 Added contest_id {} to LeetCode config",
+                            contest_id
+                        );
+                        vec.push(contest_id);
+                        changed = true;
+                    }
+                    return changed;
                 }
                 ("truncate", Some(arg_matches)) => {
                     let vec;
@@ -467,6 +1400,30 @@ impl WebsiteTrait for LeetcodeWeb {
                 }
                 _ => {}
             },
+            ("contests", Some(arg_matches)) => match arg_matches.subcommand() {
+                ("list", Some(arg_matches)) => {
+                    let count = match arg_matches.value_of("count") {
+                        Some(count) => match count.parse::<usize>() {
+                            Ok(count) => count,
+                            Err(_) => {
+                                println!("[INFO] ❌ Invalid contest count {}", count);
+                                return false;
+                            }
+                        },
+                        None => 10,
+                    };
+
+                    let web = LeetcodeWeb::create(
+                        settings.verbose,
+                        settings.config.clone(),
+                        runtime,
+                        false,
+                    );
+                    web.print_contest_list(count, &settings.date, settings.theme);
+                    return false;
+                }
+                _ => {}
+            },
             ("set", _) => {
                 settings.config.website = String::from("leetcode");
                 println!("[INFO] 🔧 Set website to LeetCode",);
@@ -0,0 +1,1571 @@
+use std::{
+    fs,
+    path::Path,
+    process::{Command, Output},
+};
+
+const CONFIG_PATH: &str = "tests/fixtures/conf.json";
+const FIXTURE_DIR: &str = "tests/fixtures/leetcode";
+
+fn run(args: &[&str]) -> Output {
+    return Command::new(env!("CARGO_BIN_EXE_contest-rank"))
+        .args(args)
+        .output()
+        .unwrap();
+}
+
+// with the clock fixed at `now`, unix seconds
+fn run_at(now: i64, args: &[&str]) -> Output {
+    return Command::new(env!("CARGO_BIN_EXE_contest-rank"))
+        .args(args)
+        .env("CONTEST_RANK_NOW", now.to_string())
+        .output()
+        .unwrap();
+}
+
+// a fresh copy of the test config for commands that write it back
+fn scratch_config(name: &str) -> String {
+    let config_path = format!("{}/{}", env!("CARGO_TARGET_TMPDIR"), name);
+    fs::copy(CONFIG_PATH, &config_path).unwrap();
+    return config_path;
+}
+
+// a fresh copy of the fixture dir with the fixtures `keep` accepts by content
+fn scratch_fixture_dir(name: &str, keep: fn(&str) -> bool) -> String {
+    let fixture_dir = format!("{}/{}", env!("CARGO_TARGET_TMPDIR"), name);
+    let _ = fs::remove_dir_all(&fixture_dir);
+    fs::create_dir_all(&fixture_dir).unwrap();
+    for entry in fs::read_dir(FIXTURE_DIR).unwrap() {
+        let entry = entry.unwrap();
+        let content = fs::read_to_string(entry.path()).unwrap();
+        if keep(&content) {
+            fs::write(Path::new(&fixture_dir).join(entry.file_name()), content).unwrap();
+        }
+    }
+    return fixture_dir;
+}
+
+#[test]
+fn leetcode_render_from_fixtures() {
+    let output = run(&["-c", CONFIG_PATH, "--replay", FIXTURE_DIR]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("LeetCode Weekly Contest 227"), "{}", stdout);
+    assert!(!stdout.contains("[Error]"), "{}", stdout);
+
+    // watched users only, ordered by local rank
+    let alice = stdout.find("alice").unwrap();
+    let bob = stdout.find("bob").unwrap();
+    assert!(alice < bob, "{}", stdout);
+    assert!(!stdout.contains("carol"), "{}", stdout);
+}
+
+#[test]
+fn leetcode_render_marks_absent_users() {
+    let output = run(&["-c", CONFIG_PATH, "--replay", FIXTURE_DIR, "-q"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    // alice's history marks b45 as skipped, so only bob is searched there
+    let biweekly = stdout.find("Biweekly Contest 45").unwrap();
+    assert!(
+        stdout[biweekly..].contains("alice                    did not participate"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("Overall Data"), "{}", stdout);
+}
+
+#[test]
+fn leetcode_replay_without_fixture_fails_offline() {
+    let output = run(&["-c", CONFIG_PATH, "--replay", "tests/fixtures/missing"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(stdout.contains("fixture not found"), "{}", stdout);
+    assert!(!stdout.contains("Weekly Contest 227"), "{}", stdout);
+}
+
+#[test]
+fn leetcode_replay_skips_disk_cache() {
+    let work = format!("{}/replay_cache", env!("CARGO_TARGET_TMPDIR"));
+    let _ = fs::remove_dir_all(&work);
+    fs::create_dir_all(&work).unwrap();
+    let root = env!("CARGO_MANIFEST_DIR");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_contest-rank"))
+        .args(&[
+            "-c",
+            &format!("{}/{}", root, CONFIG_PATH),
+            "--replay",
+            &format!("{}/{}", root, FIXTURE_DIR),
+            "--set",
+            "leetcode.cache=true",
+        ])
+        .current_dir(&work)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("LeetCode Weekly Contest 227"), "{}", stdout);
+    assert!(!Path::new(&work).join("cache").exists());
+}
+
+#[test]
+fn leetcode_contest_list_from_fixtures() {
+    // between b45 and w227, so w227 is still upcoming
+    let output = run_at(
+        1612040000,
+        &[
+            "-c",
+            CONFIG_PATH,
+            "--replay",
+            FIXTURE_DIR,
+            "leetcode",
+            "contests",
+            "list",
+            "-n",
+            "2",
+        ],
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    let recent = stdout.find("Recent").unwrap();
+    assert!(stdout[..recent].contains("w9999"), "{}", stdout);
+    assert!(stdout[..recent].contains("w227"), "{}", stdout);
+    assert!(stdout[recent..].contains("b45"), "{}", stdout);
+    assert!(stdout[recent..].contains("w226"), "{}", stdout);
+    // non weekly/biweekly contests are hidden
+    assert!(!stdout.contains("LCCI"), "{}", stdout);
+
+    let output = run(&[
+        "-c",
+        CONFIG_PATH,
+        "--replay",
+        FIXTURE_DIR,
+        "leetcode",
+        "contests",
+        "list",
+        "-n",
+        "two",
+    ]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Invalid contest count two"), "{}", stdout);
+    assert!(!stdout.contains("Recent"), "{}", stdout);
+}
+
+#[test]
+fn leetcode_contest_add_last_from_fixtures() {
+    let config_path = scratch_config("conf_contest_add.json");
+
+    // after w227, the 2100 contest in the list is still upcoming
+    let output = run_at(
+        1612100000,
+        &[
+            "-c",
+            &config_path,
+            "--replay",
+            FIXTURE_DIR,
+            "leetcode",
+            "contest",
+            "add",
+            "--last",
+            "3",
+        ],
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Config written"), "{}", stdout);
+
+    // w227 and b45 are already configured, the rest are appended oldest first
+    let config: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&config_path).unwrap()).unwrap();
+    assert_eq!(
+        config["leetcode"]["contests"],
+        serde_json::json!(["w227", "b45", "w226"])
+    );
+}
+
+#[test]
+fn leetcode_history_from_fixtures() {
+    let output = run(&["-c", CONFIG_PATH, "--replay", FIXTURE_DIR, "--history"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success(), "{}", stdout);
+    assert!(!stdout.contains("[ERROR]"), "{}", stdout);
+    // bob attended all three contests, alice skipped the biweekly one
+    assert!(stdout.contains("Biweekly Contest 45"), "{}", stdout);
+    assert_eq!(
+        stdout.matches("Weekly Contest 226").count(),
+        2,
+        "{}",
+        stdout
+    );
+    assert_eq!(
+        stdout.matches("Biweekly Contest 45").count(),
+        1,
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("1562(+62)"), "{}", stdout);
+
+    let output = run(&[
+        "-c",
+        CONFIG_PATH,
+        "--replay",
+        FIXTURE_DIR,
+        "--history",
+        "--history-last",
+        "1",
+    ]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("Weekly Contest 226"), "{}", stdout);
+
+    let output = run(&[
+        "-c",
+        CONFIG_PATH,
+        "--replay",
+        FIXTURE_DIR,
+        "--history",
+        "--history-last",
+        "all",
+    ]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success(), "{}", stdout);
+    assert!(
+        stdout.contains("[ERROR] Invalid --history-last all"),
+        "{}",
+        stdout
+    );
+    assert!(!stdout.contains("Weekly Contest"), "{}", stdout);
+}
+
+#[test]
+fn leetcode_markdown_links_problems() {
+    let output = run(&["-c", CONFIG_PATH, "--replay", FIXTURE_DIR, "-f", "markdown"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(
+        stdout.contains("## LeetCode Weekly Contest 227"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("[T4](https://leetcode.com/problems/closest-subsequence-sum/)"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("| 🏅️ | alice | United States | 12 | 00:35:00 | 2 |"),
+        "{}",
+        stdout
+    );
+
+    let output = run(&["-c", CONFIG_PATH, "--replay", FIXTURE_DIR, "--full-title"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("T4 Closest Subsequence Sum (Hard, 6pt)"),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn leetcode_attempts_from_fixtures() {
+    let output = run(&["-c", CONFIG_PATH, "--replay", FIXTURE_DIR, "--attempts"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(!stdout.contains("[WARN]"), "{}", stdout);
+    assert!(stdout.contains("00:09:00     ❌Wrong Answer"), "{}", stdout);
+    assert!(
+        stdout.contains("00:50:00     ❌Time Limit Exceeded"),
+        "{}",
+        stdout
+    );
+    // submissions outside the contest window are ignored
+    assert!(!stdout.contains("25:00:00"), "{}", stdout);
+    assert!(stdout.contains("python3"), "{}", stdout);
+}
+
+#[test]
+fn leetcode_participation_check_can_be_skipped() {
+    // every fixture except the user histories
+    let fixture_dir = scratch_fixture_dir("fixtures_no_history", |content| {
+        !content.contains("userContestRankingHistory")
+    });
+
+    let output = run(&["-c", CONFIG_PATH, "--replay", &fixture_dir, "-v", "-q"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Participation unknown"), "{}", stdout);
+
+    let output = run(&[
+        "-c",
+        CONFIG_PATH,
+        "--replay",
+        &fixture_dir,
+        "--set",
+        "leetcode.check_participation=false",
+        "-v",
+        "-q",
+    ]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("LeetCode Weekly Contest 227"), "{}", stdout);
+    assert!(!stdout.contains("Participation unknown"), "{}", stdout);
+    assert!(!stdout.contains("fixture not found"), "{}", stdout);
+}
+
+#[test]
+fn leetcode_attempts_outside_recent_window() {
+    let fixture_dir = scratch_fixture_dir("fixtures_full_recent", |_| true);
+
+    // bob's public list is full with submissions made after both contests
+    let path = Path::new(&fixture_dir).join("leetcode_com_graphql_0e3c290e121a8fc3.json");
+    let mut fixture: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(fixture["payload"]["variables"]["username"], "bob");
+    let recent: Vec<serde_json::Value> = (0..20)
+        .map(|idx| {
+            serde_json::json!({
+                "titleSlug": "two-sum",
+                "timestamp": (1612150000 + idx * 60).to_string(),
+                "statusDisplay": "Accepted",
+                "lang": "rust",
+            })
+        })
+        .collect();
+    fixture["body"]["data"]["recentSubmissionList"] = serde_json::json!(recent);
+    fs::write(&path, fixture.to_string()).unwrap();
+
+    let output = run(&["-c", CONFIG_PATH, "--replay", &fixture_dir, "--attempts"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("[WARN] When fetching attempts, user=bob, e=contest is older than the last 20 submissions"),
+        "{}",
+        stdout
+    );
+    // no attempt timeline is made up for bob
+    assert!(!stdout.contains("Runtime Error"), "{}", stdout);
+    // alice's list still covers w227
+    assert!(
+        stdout.contains("00:50:00     ❌Time Limit Exceeded"),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn leetcode_question_stats_from_fixtures() {
+    let output = run(&["-c", CONFIG_PATH, "--replay", FIXTURE_DIR, "-f", "markdown"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(
+        stdout.contains("| 1/2 | alice | 00:30:00 | 00:30:00 | 0.00 | 100% |"),
+        "{}",
+        stdout
+    );
+    // median of 00:15:00 and 00:25:00, one of two solvers was bug free
+    assert!(
+        stdout.contains("| 2/2 | alice | 00:15:00 | 00:20:00 | 0.50 | 50% |"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("| 0/2 | - | - | - | 0.00 | 0% |"),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn leetcode_compare_from_fixtures() {
+    let output = run(&[
+        "-c",
+        CONFIG_PATH,
+        "--replay",
+        FIXTURE_DIR,
+        "compare",
+        "alice",
+        "bob",
+        "--matrix",
+    ]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success(), "{}", stdout);
+    // alice finished ahead in w227 and skipped b45, so only one duel counts
+    assert!(
+        stdout.contains("📊alice 1 : 0 bob  (questions 3 : 0)"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("⚡️alice                    by 00:04:00"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("Head-to-head matrix"), "{}", stdout);
+
+    // one user is neither a duel nor the whole group
+    let output = run(&[
+        "-c",
+        CONFIG_PATH,
+        "--replay",
+        FIXTURE_DIR,
+        "compare",
+        "alice",
+    ]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success(), "{}", stderr);
+    assert!(stderr.contains("requires at least 2 values"), "{}", stderr);
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn leetcode_season_standings_from_fixtures() {
+    let output = run(&[
+        "-c",
+        "tests/fixtures/conf_season.json",
+        "--replay",
+        FIXTURE_DIR,
+        "-f",
+        "markdown",
+    ]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(
+        stdout.contains("## Season Winter 2021 (2 contests, best 1)"),
+        "{}",
+        stdout
+    );
+    // bob's second place is dropped, but both attendances earn the bonus
+    assert!(
+        stdout.contains("| 🏅️ | bob | 35 | 1 | 2 | 18 |"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("| 🥈 | alice | 30 | 1 | 1 | 0 |"),
+        "{}",
+        stdout
+    );
+
+    let weekly = stdout.find("## Season Weekly only (1 contests)").unwrap();
+    assert!(
+        stdout[weekly..].contains("| 🏅️ | alice | 25 | 1 | 1 | 0 |"),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn leetcode_aggregate_stats_in_json() {
+    let output = run(&["-c", CONFIG_PATH, "--replay", FIXTURE_DIR, "-f", "json"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let object: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+
+    let bob = &object["aggregate"][0];
+    assert_eq!(bob["username"], "bob");
+    assert_eq!(bob["win_streak"], 1);
+    assert_eq!(bob["avg_percentile"], 12.5);
+    assert_eq!(bob["best_contest"]["name"], "LeetCode Biweekly Contest 45");
+    // from top 10% in b45 down to top 15% in w227
+    assert_eq!(bob["trend"], -5.0);
+
+    let alice = &object["aggregate"][1];
+    assert_eq!(alice["trend"], serde_json::Value::Null);
+    assert_eq!(object["data"][0]["participant_count"], 20);
+}
+
+#[test]
+fn leetcode_charts_from_fixtures() {
+    let output = run(&["-c", CONFIG_PATH, "--replay", FIXTURE_DIR, "chart", "rank"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Global rank (log scale)"), "{}", stdout);
+    // contests are plotted oldest first
+    assert!(stdout.contains("b45   w227"), "{}", stdout);
+    assert!(stdout.contains("bob                      ▁█"), "{}", stdout);
+
+    let output = run(&[
+        "-c",
+        CONFIG_PATH,
+        "--replay",
+        FIXTURE_DIR,
+        "chart",
+        "rating",
+    ]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("1602 │ ▲"), "{}", stdout);
+    // w226 is in bob's history but not in the config
+    assert!(!stdout.contains("w226"), "{}", stdout);
+
+    let output = run(&[
+        "-c",
+        CONFIG_PATH,
+        "--replay",
+        FIXTURE_DIR,
+        "chart",
+        "times",
+        "--contest",
+        "w227",
+    ]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout
+            .contains("alice                    ████████████████████████████████████████ 00:30:00"),
+        "{}",
+        stdout
+    );
+
+    let output = run(&[
+        "-c",
+        CONFIG_PATH,
+        "--replay",
+        FIXTURE_DIR,
+        "chart",
+        "rank",
+        "--height",
+        "tall",
+    ]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success(), "{}", stdout);
+    assert!(
+        stdout.contains("[ERROR] Invalid --height tall"),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn leetcode_svg_charts_from_fixtures() {
+    let path = format!("{}/rank.svg", env!("CARGO_TARGET_TMPDIR"));
+    let output = run(&[
+        "-c",
+        CONFIG_PATH,
+        "--replay",
+        FIXTURE_DIR,
+        "chart",
+        "rank",
+        "--svg",
+        &path,
+    ]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Chart written"), "{}", stdout);
+
+    let svg = fs::read_to_string(&path).unwrap();
+    assert!(svg.starts_with("<svg"), "{}", svg);
+    assert!(svg.contains("<polyline"), "{}", svg);
+    assert!(svg.contains("<title>bob 980</title>"), "{}", svg);
+
+    let path = format!("{}/times.svg", env!("CARGO_TARGET_TMPDIR"));
+    run(&[
+        "-c",
+        CONFIG_PATH,
+        "--replay",
+        FIXTURE_DIR,
+        "--attempts",
+        "chart",
+        "times",
+        "--contest",
+        "w227",
+        "--svg",
+        &path,
+    ]);
+    let svg = fs::read_to_string(&path).unwrap();
+    assert!(svg.contains("<title>T3 00:30:00</title>"), "{}", svg);
+    assert!(
+        svg.contains("<title>T1 Runtime Error 00:05:00</title>"),
+        "{}",
+        svg
+    );
+}
+
+#[test]
+fn leetcode_dates_follow_locale_and_timezone() {
+    let args = [
+        "-c",
+        CONFIG_PATH,
+        "--replay",
+        FIXTURE_DIR,
+        "--locale",
+        "de_DE",
+        "--timezone",
+        "Europe/Berlin",
+        "--date-format",
+        "%A %d. %B %Y %H:%M",
+    ];
+    let output = run(&args);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Sonntag 31. Januar 2021 03:30"),
+        "{}",
+        stdout
+    );
+
+    let output = run(&[&args[..], &["-f", "markdown"]].concat());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Samstag 30. Januar 2021 15:30"),
+        "{}",
+        stdout
+    );
+
+    let output = run(&[
+        "-c",
+        CONFIG_PATH,
+        "--replay",
+        FIXTURE_DIR,
+        "--timezone",
+        "Mars/Olympus_Mons",
+    ]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("[WARN] Unknown timezone"), "{}", stdout);
+}
+
+#[test]
+fn leetcode_plain_themes() {
+    // stdout is a pipe here, so auto falls back to ascii
+    let output = run(&[
+        "-c",
+        CONFIG_PATH,
+        "--replay",
+        FIXTURE_DIR,
+        "--theme",
+        "auto",
+        "--date-format",
+        "%Y-%m-%d %H:%M",
+    ]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.is_ascii(), "{}", stdout);
+    assert!(
+        stdout.contains("  #1  alice                    time 00:35:00     rank 2    score 12"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("    [AC] T2     score 4             at 00:15:00     bugs 1       cpp"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("  -   alice                    did not participate"),
+        "{}",
+        stdout
+    );
+
+    let output = run(&[
+        "-c",
+        CONFIG_PATH,
+        "--replay",
+        FIXTURE_DIR,
+        "--theme",
+        "color",
+    ]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // escape sequences don't count towards the column width
+    assert!(
+        stdout.contains("\x1b[32mBUG FREE\x1b[0m     cpp"),
+        "{}",
+        stdout
+    );
+
+    // charts and markdown follow the theme too, markdown never gets colors
+    let output = run(&[
+        "-c",
+        CONFIG_PATH,
+        "--replay",
+        FIXTURE_DIR,
+        "--theme",
+        "ascii",
+        "chart",
+        "times",
+        "--contest",
+        "w227",
+    ]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("📊"), "{}", stdout);
+    assert!(!stdout.contains("🏅️"), "{}", stdout);
+    assert!(
+        stdout.contains("== LeetCode Weekly Contest 227 solve times"),
+        "{}",
+        stdout
+    );
+    let output = run(&[
+        "-c",
+        CONFIG_PATH,
+        "--replay",
+        FIXTURE_DIR,
+        "--theme",
+        "color",
+        "-f",
+        "markdown",
+    ]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("\x1b["), "{}", stdout);
+    assert!(
+        stdout.contains("| #1 | alice | United States | 12 | 00:35:00 | 2 | [AC] 00:06:00 `cpp` | [AC] 00:15:00 bugs 1 `cpp` |"),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn leetcode_ascii_theme_is_pure_ascii() {
+    let commands: [&[&str]; 5] = [
+        &[],
+        &["chart", "rank"],
+        &["chart", "rating"],
+        &["chart", "times", "--contest", "w227"],
+        &["leetcode", "contests", "list"],
+    ];
+    for command in commands.iter() {
+        let mut args = vec![
+            "-c",
+            CONFIG_PATH,
+            "--replay",
+            FIXTURE_DIR,
+            "--theme",
+            "ascii",
+            // the fixture locale prints localized weekdays
+            "--date-format",
+            "%Y-%m-%d %H:%M",
+        ];
+        args.extend_from_slice(command);
+        let output = run_at(1612040000, &args);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        assert!(output.status.success(), "{}", stdout);
+        // [INFO] log lines keep their emoji, the rendered output must not
+        let rendered: Vec<&str> = stdout
+            .lines()
+            .filter(|line| !line.starts_with("[INFO]"))
+            .collect();
+        assert!(rendered.len() > 1, "{:?}: {}", command, stdout);
+        assert!(
+            rendered.iter().all(|line| line.is_ascii()),
+            "{:?}: {}",
+            command,
+            stdout
+        );
+    }
+}
+
+#[test]
+fn leetcode_columns_sort_and_filter() {
+    let output = run(&[
+        "-c",
+        CONFIG_PATH,
+        "--replay",
+        FIXTURE_DIR,
+        "--columns",
+        "country,score",
+        "--sort",
+        "name",
+        "-q",
+    ]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("  🏅️alice                    🌏United States    ✨12"),
+        "{}",
+        stdout
+    );
+    // absent players stay last whatever the sort key
+    assert!(
+        stdout.contains("  🏅️bob                      🌏-                ✨12    \n  💤alice"),
+        "{}",
+        stdout
+    );
+
+    let output = run(&[
+        "-c",
+        CONFIG_PATH,
+        "--replay",
+        FIXTURE_DIR,
+        "--only-countries",
+        "united states",
+        "--top",
+        "1",
+    ]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("  🏅️alice"), "{}", stdout);
+    assert!(!stdout.contains("  🥈bob"), "{}", stdout);
+    assert!(!stdout.contains("  🏅️bob"), "{}", stdout);
+
+    let output = run(&["-c", CONFIG_PATH, "--replay", FIXTURE_DIR, "--top", "abc"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success(), "{}", stdout);
+    assert!(stdout.contains("[ERROR] Invalid --top abc"), "{}", stdout);
+    assert!(!stdout.contains("Weekly Contest 227"), "{}", stdout);
+}
+
+#[test]
+fn leetcode_saved_views() {
+    let config_path = scratch_config("conf_views.json");
+
+    let output = run(&[
+        "-c",
+        &config_path,
+        "--columns",
+        "score",
+        "--only-users",
+        "bob",
+        "--save-view",
+        "bob",
+    ]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("View bob saved"), "{}", stdout);
+
+    let config: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&config_path).unwrap()).unwrap();
+    assert_eq!(
+        config["views"]["bob"]["columns"],
+        serde_json::json!(["score"])
+    );
+    assert_eq!(config["views"]["bob"]["users"], serde_json::json!(["bob"]));
+
+    let output = run(&[
+        "-c",
+        &config_path,
+        "--replay",
+        FIXTURE_DIR,
+        "--view",
+        "bob",
+        "-q",
+    ]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("  🥈bob                      ✨7     \n"),
+        "{}",
+        stdout
+    );
+    assert!(!stdout.contains("alice"), "{}", stdout);
+}
+
+#[test]
+fn leetcode_country_aggregate_and_leaders() {
+    let output = run(&["-c", CONFIG_PATH, "--replay", FIXTURE_DIR, "-q"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // bob has no country on the ranking pages and is left out
+    assert!(
+        stdout.contains("🌏Countries\n  🌏United States            👥1    ⚡️1    ⬆️alice ✨12\n"),
+        "{}",
+        stdout
+    );
+
+    // carol isn't watched but leads the ranking pages for China
+    let output = run(&[
+        "-c",
+        CONFIG_PATH,
+        "--replay",
+        FIXTURE_DIR,
+        "-q",
+        "country",
+        "china",
+        "--contest",
+        "w227",
+    ]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("LeetCode Weekly Contest 227 (China)"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("  🏅️carol"), "{}", stdout);
+    assert!(!stdout.contains("alice"), "{}", stdout);
+
+    let output = run(&[
+        "-c",
+        CONFIG_PATH,
+        "--replay",
+        FIXTURE_DIR,
+        "country",
+        "Japan",
+    ]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("No participant found, country=Japan"),
+        "{}",
+        stdout
+    );
+
+    let output = run(&[
+        "-c",
+        CONFIG_PATH,
+        "--replay",
+        FIXTURE_DIR,
+        "country",
+        "China",
+        "--top",
+        "ten",
+    ]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success(), "{}", stdout);
+    assert!(stdout.contains("[ERROR] Invalid --top ten"), "{}", stdout);
+}
+
+#[test]
+fn leetcode_global_top_and_neighbours() {
+    let output = run(&[
+        "-c",
+        CONFIG_PATH,
+        "--replay",
+        FIXTURE_DIR,
+        "-q",
+        "--top-global",
+        "1",
+    ]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // carol leads both contests but takes no local rank or win from friends
+    assert!(
+        stdout.contains(
+            "  👀carol                    🍺00:40:00     📊1    ✨18     🐂AK\n  🏅️alice"
+        ),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout
+            .contains("  👀carol                    🍺00:45:00     📊1    ✨19     🐂AK\n  🏅️bob"),
+        "{}",
+        stdout
+    );
+    let overall = stdout.find("Overall Data").unwrap();
+    assert!(!stdout[overall..].contains("carol"), "{}", stdout);
+    assert!(
+        stdout.contains("  🏅️bob                      ✨19     🏅️1   ⚡️2"),
+        "{}",
+        stdout
+    );
+
+    // neighbours are the entries next to a watched user on the ranking pages
+    let output = run(&[
+        "-c",
+        CONFIG_PATH,
+        "--replay",
+        FIXTURE_DIR,
+        "-f",
+        "json",
+        "--neighbours",
+        "1",
+    ]);
+    let object: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let players = |contest: usize| -> Vec<(String, bool)> {
+        object["data"][contest]["players"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|player| {
+                (
+                    player["username"].as_str().unwrap().to_string(),
+                    player["is_friend"].as_bool().unwrap(),
+                )
+            })
+            .collect()
+    };
+    assert_eq!(
+        players(0),
+        vec![
+            (String::from("carol"), false),
+            (String::from("alice"), true),
+            (String::from("bob"), true),
+        ]
+    );
+    assert_eq!(
+        players(1),
+        vec![
+            (String::from("carol"), false),
+            (String::from("bob"), true),
+            (String::from("alice"), true),
+        ]
+    );
+
+    for flag in ["--top-global", "--neighbours"].iter() {
+        let output = run(&["-c", CONFIG_PATH, "--replay", FIXTURE_DIR, flag, "x"]);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(!output.status.success(), "{}", stdout);
+        assert!(
+            stdout.contains(&format!("[ERROR] Invalid {} x", flag)),
+            "{}",
+            stdout
+        );
+    }
+}
+
+#[test]
+fn leetcode_group_team_standings() {
+    let config_path = scratch_config("conf_groups.json");
+
+    let output = run(&["-c", &config_path, "group", "add", "red", "alice", "zed"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("User zed isn't watched"), "{}", stdout);
+    run(&["-c", &config_path, "group", "add", "blue", "bob"]);
+    let output = run(&["-c", &config_path, "group", "delete", "red", "zed"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Config written"), "{}", stdout);
+
+    let config: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&config_path).unwrap()).unwrap();
+    assert_eq!(
+        config["groups"],
+        serde_json::json!({ "blue": ["bob"], "red": ["alice"] })
+    );
+
+    let output = run(&["-c", &config_path, "--replay", FIXTURE_DIR, "-q"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains(
+            "  👥Teams\n    🏅️red                      ✨12     ⚡️1    ⬆️alice #1\n    🥈blue                     ✨7      ⚡️1    ⬆️bob #2\n"
+        ),
+        "{}",
+        stdout
+    );
+    // alice skipped b45, so red scores nothing there
+    assert!(
+        stdout.contains("    🥈red                      ✨0      ⚡️0    ⬆️-\n"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains(
+            "👥Team Standings\n  🏅️blue                     ✨19     🏅️1   ⚡️2    ⬆️bob\n  🥈red                      ✨12     🏅️1   ⚡️1    ⬆️alice\n"
+        ),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn leetcode_aliases_replace_usernames() {
+    let config_path = format!("{}/conf_aliases.json", env!("CARGO_TARGET_TMPDIR"));
+    let mut config: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(CONFIG_PATH).unwrap()).unwrap();
+    config["aliases"] = serde_json::json!({
+        "alice": { "name": "Alice Liddell", "emoji": "🐇" },
+        "bob": { "name": "Bob" },
+        "leetcode:bob": { "name": "Robert", "avatar": "https://example.com/bob.png" },
+        "codeforces:alice": { "name": "Wrong Judge" },
+    });
+    fs::write(&config_path, config.to_string()).unwrap();
+
+    let output = run(&["-c", &config_path, "--replay", FIXTURE_DIR, "-q"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("  🏅️🐇Alice Liddell          🍺00:35:00"),
+        "{}",
+        stdout
+    );
+    // the website specific alias wins over the bare username
+    assert!(
+        stdout.contains("  🏅️Robert                   ✨19"),
+        "{}",
+        stdout
+    );
+    assert!(!stdout.contains("Wrong Judge"), "{}", stdout);
+    assert!(!stdout.contains("bob"), "{}", stdout);
+
+    let output = run(&[
+        "-c",
+        &config_path,
+        "--replay",
+        FIXTURE_DIR,
+        "-f",
+        "markdown",
+        "-q",
+    ]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains(
+            "| 🏅️ | <img src=\"https://example.com/bob.png\" width=\"20\"> Robert |  | 12 |"
+        ),
+        "{}",
+        stdout
+    );
+
+    let output = run(&[
+        "-c",
+        &config_path,
+        "--replay",
+        FIXTURE_DIR,
+        "compare",
+        "alice",
+        "bob",
+    ]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("🐇Alice Liddell vs Robert"), "{}", stdout);
+
+    let output = run(&[
+        "-c",
+        &config_path,
+        "--replay",
+        FIXTURE_DIR,
+        "-f",
+        "json",
+        "-q",
+    ]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let object: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    let players = object["data"][0]["players"].as_array().unwrap();
+    let alice = players
+        .iter()
+        .find(|player| player["username"] == "alice")
+        .unwrap();
+    assert_eq!(alice["alias"]["name"], "Alice Liddell");
+    assert_eq!(alice["alias"]["emoji"], "🐇");
+    let bob = &object["aggregate"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|aggregate| aggregate["username"] == "bob")
+        .unwrap();
+    assert_eq!(bob["alias"]["name"], "Robert");
+    assert_eq!(bob["alias"]["avatar"], "https://example.com/bob.png");
+}
+
+#[test]
+fn leetcode_profiles_keep_separate_watch_lists() {
+    let config_path = scratch_config("conf_profiles.json");
+
+    run(&["-c", &config_path, "profile", "add", "work"]);
+    run(&["-c", &config_path, "profile", "add", "uni", "--copy"]);
+    let output = run(&["-c", &config_path, "profile", "add", "default"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Config file unchanged"), "{}", stdout);
+
+    run(&[
+        "-c",
+        &config_path,
+        "--profile",
+        "work",
+        "leetcode",
+        "user",
+        "add",
+        "carol",
+    ]);
+    run(&[
+        "-c",
+        &config_path,
+        "--profile",
+        "work",
+        "leetcode",
+        "contest",
+        "add",
+        "w227",
+    ]);
+
+    let config: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&config_path).unwrap()).unwrap();
+    assert_eq!(
+        config["leetcode"]["users"],
+        serde_json::json!(["alice", "bob"])
+    );
+    assert_eq!(
+        config["profiles"]["work"]["users"],
+        serde_json::json!(["carol"])
+    );
+    assert_eq!(
+        config["profiles"]["work"]["contests"],
+        serde_json::json!(["w227"])
+    );
+    assert_eq!(
+        config["profiles"]["uni"]["users"],
+        serde_json::json!(["alice", "bob"])
+    );
+
+    let output = run(&["-c", &config_path, "profile", "use", "work"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Config written"), "{}", stdout);
+    let output = run(&["-c", &config_path, "profile", "list"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("* work"), "{}", stdout);
+
+    // the current profile applies without --profile
+    let output = run(&["-c", &config_path, "--replay", FIXTURE_DIR, "-q"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("carol"), "{}", stdout);
+    assert!(!stdout.contains("alice"), "{}", stdout);
+
+    let output = run(&[
+        "-c",
+        &config_path,
+        "--profile",
+        "default",
+        "--replay",
+        FIXTURE_DIR,
+        "-q",
+    ]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("alice"), "{}", stdout);
+    assert!(!stdout.contains("carol"), "{}", stdout);
+
+    let output = run(&[
+        "-c",
+        &config_path,
+        "--profile",
+        "nope",
+        "--replay",
+        FIXTURE_DIR,
+        "-q",
+    ]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Profile doesn't exist"), "{}", stdout);
+    assert!(stdout.contains("alice"), "{}", stdout);
+
+    run(&["-c", &config_path, "profile", "delete", "work"]);
+    let config: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&config_path).unwrap()).unwrap();
+    assert!(config["profiles"].get("work").is_none());
+    assert_eq!(config["current_profile"], serde_json::json!(""));
+}
+
+#[test]
+fn config_check_and_migration() {
+    let config_path = format!("{}/conf_legacy.json", env!("CARGO_TARGET_TMPDIR"));
+    let backup_path = format!("{}.v0.bak", config_path);
+    let _ = fs::remove_file(&backup_path);
+    let mut config: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(CONFIG_PATH).unwrap()).unwrap();
+    config.as_object_mut().unwrap().remove("version");
+    fs::write(&config_path, config.to_string()).unwrap();
+
+    let output = run(&["-c", &config_path, "config", "check"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("will be migrated to 1"), "{}", stdout);
+
+    let output = run(&["-c", &config_path, "--replay", FIXTURE_DIR, "-q"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Config migrated from version 0 to 1"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("alice"), "{}", stdout);
+    let migrated: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&config_path).unwrap()).unwrap();
+    assert_eq!(migrated["version"], serde_json::json!(1));
+    assert_eq!(
+        migrated["leetcode"]["users"],
+        serde_json::json!(["alice", "bob"])
+    );
+    let backup: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&backup_path).unwrap()).unwrap();
+    assert_eq!(backup, config);
+
+    let mut config = migrated;
+    config["leetcode"]["contests"] = serde_json::json!(["w227", "x12", "w227"]);
+    config["leetcode"]["users"] = serde_json::json!(["alice", "bob", "alice"]);
+    config["leetcode"]["concurrent"] = serde_json::json!(0);
+    fs::write(&config_path, config.to_string()).unwrap();
+
+    let output = run(&["-c", &config_path, "config", "check"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success(), "{}", stdout);
+    assert!(
+        stdout.contains(
+            "[ERROR] leetcode.contests[1]: invalid contest id \"x12\", expected w<number> or b<number>"
+        ),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("[ERROR] leetcode.contests[2]: duplicate contest \"w227\", first at leetcode.contests[0]"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains(
+            "[ERROR] leetcode.users[2]: duplicate user \"alice\", first at leetcode.users[0]"
+        ),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("[ERROR] leetcode.concurrent:"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("4 error(s) and 0 warning(s)"), "{}", stdout);
+
+    // rendering refuses to run, editing still works to fix the config
+    let output = run(&["-c", &config_path, "--replay", FIXTURE_DIR, "-q"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success(), "{}", stdout);
+    assert!(stdout.contains("Config has 4 error(s)"), "{}", stdout);
+    let output = run(&["-c", &config_path, "leetcode", "contest", "delete", "x12"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Config written"), "{}", stdout);
+
+    // a broken file is reported with its location instead of replaced
+    let broken = "{\n  \"version\": 1,\n  \"website\": \"leetcode\",\n  \"leetcode\": {\"users\": [\"alice\"],}\n}";
+    fs::write(&config_path, broken).unwrap();
+    let output = run(&["-c", &config_path, "--replay", FIXTURE_DIR]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success(), "{}", stdout);
+    assert!(stdout.contains("line 4 column"), "{}", stdout);
+    assert_eq!(fs::read_to_string(&config_path).unwrap(), broken);
+
+    let mut config = config;
+    config["leetcode"]["concurrent"] = serde_json::json!("two");
+    fs::write(&config_path, serde_json::to_string_pretty(&config).unwrap()).unwrap();
+    let output = run(&["-c", &config_path, "config", "check"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success(), "{}", stdout);
+    assert!(
+        stdout.contains("[ERROR] leetcode.concurrent: invalid type"),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn config_toml_and_yaml_formats() {
+    let yaml_path = format!("{}/conf_format.yaml", env!("CARGO_TARGET_TMPDIR"));
+    fs::write(
+        &yaml_path,
+        "version: 1\nwebsite: leetcode\ntheme: emoji\nleetcode:\n  users: [alice, bob]\n  contests: [w227]\n  live_users: []\n  live_contests: []\n  max_rank: 25\n  concurrent: 1\n  cache: false\nlive:\n  interval: 10\n  last: 5400\n",
+    )
+    .unwrap();
+    let output = run(&["-c", &yaml_path, "--replay", FIXTURE_DIR, "-q"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("LeetCode Weekly Contest 227"), "{}", stdout);
+    run(&["-c", &yaml_path, "leetcode", "user", "add", "carol"]);
+    let text = fs::read_to_string(&yaml_path).unwrap();
+    assert!(text.contains("- carol"), "{}", text);
+    let output = run(&["-c", &yaml_path, "--show-config"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.starts_with("🔧 Config loaded from yaml :"),
+        "{}",
+        stdout
+    );
+
+    let toml_path = format!("{}/conf_format.toml", env!("CARGO_TARGET_TMPDIR"));
+    let _ = fs::remove_file(&toml_path);
+    run(&["-c", &toml_path, "leetcode", "user", "add", "alice"]);
+    run(&["-c", &toml_path, "leetcode", "contest", "add", "w227"]);
+    let text = fs::read_to_string(&toml_path).unwrap();
+    assert!(text.contains("users = [\"alice\"]"), "{}", text);
+    assert!(text.contains("[leetcode]"), "{}", text);
+    let output = run(&["-c", &toml_path, "config", "check"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+
+    fs::write(
+        &toml_path,
+        text.replace("concurrent = 1", "concurrent = \"one\""),
+    )
+    .unwrap();
+    let output = run(&["-c", &toml_path, "config", "check"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success(), "{}", stdout);
+    assert!(
+        stdout.contains("leetcode.concurrent: invalid type"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("line"), "{}", stdout);
+}
+
+#[test]
+fn config_search_path() {
+    let root = format!("{}/config_search", env!("CARGO_TARGET_TMPDIR"));
+    let _ = fs::remove_dir_all(&root);
+    let xdg = format!("{}/xdg", root);
+    let work = format!("{}/work", root);
+    fs::create_dir_all(&work).unwrap();
+    let show_path = |xdg: &str, env_path: Option<&str>| {
+        let mut command = Command::new(env!("CARGO_BIN_EXE_contest-rank"));
+        command
+            .arg("--show-config-path")
+            .current_dir(&work)
+            .env("XDG_CONFIG_HOME", xdg)
+            .env_remove("CONTEST_RANK_CONFIG");
+        if let Some(path) = env_path {
+            command.env("CONTEST_RANK_CONFIG", path);
+        }
+        let output = command.output().unwrap();
+        return String::from_utf8_lossy(&output.stdout).to_string();
+    };
+
+    // a new config goes into the XDG dir, not the current dir
+    let stdout = show_path(&xdg, None);
+    assert!(
+        stdout.contains("xdg/contest-rank/conf.json\n[INFO] Picked from XDG config dir, new file"),
+        "{}",
+        stdout
+    );
+    assert!(!Path::new(&work).join("conf.json").exists());
+    let stdout = show_path(&xdg, None);
+    assert!(
+        stdout.contains("Picked from XDG config dir\n"),
+        "{}",
+        stdout
+    );
+
+    let env_path = format!("{}/env.toml", root);
+    let stdout = show_path(&xdg, Some(&env_path));
+    assert!(
+        stdout.contains("env.toml\n[INFO] Picked from $CONTEST_RANK_CONFIG"),
+        "{}",
+        stdout
+    );
+
+    // a path that can't be created is shown as given
+    let blocker = format!("{}/blocker", root);
+    fs::write(&blocker, "").unwrap();
+    let stdout = show_path(&xdg, Some(&format!("{}/conf.json", blocker)));
+    assert!(
+        stdout.contains("blocker/conf.json\n[INFO] Picked from $CONTEST_RANK_CONFIG"),
+        "{}",
+        stdout
+    );
+
+    // without anything in the XDG dir the current dir is searched
+    fs::write(
+        Path::new(&work).join("conf.yaml"),
+        "version: 1\nwebsite: leetcode\nleetcode:\n  users: []\n  contests: []\n  live_users: []\n  live_contests: []\n  max_rank: 25\n  concurrent: 1\n  cache: false\nlive:\n  interval: 10\n  last: 5400\n",
+    )
+    .unwrap();
+    let stdout = show_path(&format!("{}/empty", root), None);
+    assert!(
+        stdout.contains("work/conf.yaml\n[INFO] Picked from current dir"),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn config_overrides_from_set_and_env() {
+    let config_path = scratch_config("conf_overrides.json");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_contest-rank"))
+        .args(&[
+            "-c",
+            &config_path,
+            "--set",
+            "leetcode.max_rank=8000",
+            "--set",
+            "leetcode.users=carol",
+            "--show-config",
+        ])
+        .env("CONTEST_RANK__LEETCODE__USERS", "alice,bob,carol")
+        .env("CONTEST_RANK__LEETCODE__CACHE", "true")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let shown: serde_json::Value = serde_json::from_str(stdout.lines().nth(1).unwrap()).unwrap();
+    assert_eq!(shown["leetcode"]["max_rank"], serde_json::json!(8000));
+    assert_eq!(shown["leetcode"]["cache"], serde_json::json!(true));
+    // --set wins over the environment
+    assert_eq!(shown["leetcode"]["users"], serde_json::json!(["carol"]));
+
+    let output = run(&[
+        "-c",
+        &config_path,
+        "--replay",
+        FIXTURE_DIR,
+        "-q",
+        "--set",
+        "leetcode.contests=w227",
+    ]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Weekly Contest 227"), "{}", stdout);
+    assert!(!stdout.contains("Biweekly Contest 45"), "{}", stdout);
+
+    // overrides never reach the file, edits still do
+    run(&[
+        "-c",
+        &config_path,
+        "--set",
+        "leetcode.max_rank=8000",
+        "leetcode",
+        "user",
+        "add",
+        "carol",
+    ]);
+    let config: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&config_path).unwrap()).unwrap();
+    assert_eq!(config["leetcode"]["max_rank"], serde_json::json!(25));
+    assert_eq!(
+        config["leetcode"]["users"],
+        serde_json::json!(["alice", "bob", "carol"])
+    );
+
+    // editing the overridden key itself can't keep both, so nothing is written
+    let output = run(&[
+        "-c",
+        &config_path,
+        "--set",
+        "leetcode.users=carol",
+        "leetcode",
+        "user",
+        "add",
+        "dave",
+    ]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("leetcode.users is overridden by --set and can't be edited"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("Config file unchanged"), "{}", stdout);
+    let config: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&config_path).unwrap()).unwrap();
+    assert_eq!(
+        config["leetcode"]["users"],
+        serde_json::json!(["alice", "bob", "carol"])
+    );
+
+    let output = run(&["-c", &config_path, "--set", "leetcode.max_rnk=5"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success(), "{}", stdout);
+    assert!(
+        stdout.contains("leetcode.max_rnk: unknown config key, from --set"),
+        "{}",
+        stdout
+    );
+    let output = Command::new(env!("CARGO_BIN_EXE_contest-rank"))
+        .args(&["-c", &config_path])
+        .env("CONTEST_RANK__LEETCODE__CONCURRENT", "four")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success(), "{}", stdout);
+    assert!(
+        stdout.contains("leetcode.concurrent: invalid type: string \"four\", expected u32, from CONTEST_RANK__LEETCODE__CONCURRENT"),
+        "{}",
+        stdout
+    );
+    let output = run(&[
+        "-c",
+        &config_path,
+        "--replay",
+        FIXTURE_DIR,
+        "--set",
+        "leetcode.concurrent=0",
+    ]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success(), "{}", stdout);
+    assert!(
+        stdout.contains("[ERROR] leetcode.concurrent:"),
+        "{}",
+        stdout
+    );
+
+    // a bad value in the file is fine when the run overrides it
+    let mut config: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&config_path).unwrap()).unwrap();
+    config["leetcode"]["concurrent"] = serde_json::json!(0);
+    fs::write(&config_path, config.to_string()).unwrap();
+    let output = run(&[
+        "-c",
+        &config_path,
+        "--replay",
+        FIXTURE_DIR,
+        "-q",
+        "--set",
+        "leetcode.concurrent=2",
+    ]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("Weekly Contest 227"), "{}", stdout);
+}